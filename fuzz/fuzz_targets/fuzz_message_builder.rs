@@ -0,0 +1,66 @@
+//! 以任意输入构造消息，`MessageBuilder::build()`不得panic。
+//!
+//! 运行：`cargo +nightly fuzz run fuzz_message_builder`
+
+#![no_main]
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+use wecom_agent::message::{MarkDownMsg, MessageBuilder, Text, TextCardMsg};
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    users: Option<Vec<String>>,
+    groups: Option<Vec<String>>,
+    tags: Option<Vec<String>>,
+    agent_id: Option<usize>,
+    safe: bool,
+    enable_id_trans: bool,
+    enable_duplicate_check: bool,
+    duplicate_check_interval: usize,
+    content: Content,
+}
+
+#[derive(Debug, Arbitrary)]
+enum Content {
+    Text(String),
+    Markdown(String),
+    TextCard {
+        title: String,
+        description: String,
+        url: String,
+    },
+}
+
+fn as_str(ids: &[String]) -> Vec<&str> {
+    ids.iter().map(String::as_str).collect()
+}
+
+fuzz_target!(|input: Input| {
+    let mut builder = MessageBuilder::default()
+        .with_safe(input.safe)
+        .with_enable_id_trans(input.enable_id_trans)
+        .with_enable_duplicate_check(input.enable_duplicate_check)
+        .with_duplicate_check_interval(input.duplicate_check_interval);
+    if let Some(users) = &input.users {
+        builder = builder.to_users(as_str(users));
+    }
+    if let Some(groups) = &input.groups {
+        builder = builder.to_groups(as_str(groups));
+    }
+    if let Some(tags) = &input.tags {
+        builder = builder.to_tags(as_str(tags));
+    }
+    if let Some(agent_id) = input.agent_id {
+        builder = builder.from_agent(agent_id);
+    }
+    let _ = match input.content {
+        Content::Text(content) => builder.build(Text::new(content)),
+        Content::Markdown(content) => builder.build(MarkDownMsg::new(content)),
+        Content::TextCard {
+            title,
+            description,
+            url,
+        } => builder.build(TextCardMsg::new(title, description, url)),
+    };
+});
@@ -0,0 +1,291 @@
+//! 应用详情、应用列表与应用设置
+
+use serde::{Deserialize, Serialize};
+
+// 获取应用的返回结果（节选）
+// {
+//     "errcode": 0,
+//     "errmsg": "ok",
+//     "agentid": 1000005,
+//     "name": "HR助手",
+//     "square_logo_url": "https://p.qlogo.cn/bizmail/FicwmI50icF8GH9ib7rUAYR5kicLTgP265naVFQKnleqSlRhiaBx7QA9u7Q/0",
+//     "description": "HR服务与员工自助平台",
+//     "allow_userinfos": {"user": [{"userid": "zhangshan"}, {"userid": "lisi"}]},
+//     "allow_partys": {"partyid": [1]},
+//     "allow_tags": {"tagid": [1, 2, 3]},
+//     "close": 0,
+//     "redirect_domain": "open.work.weixin.qq.com",
+//     "report_location_flag": 0,
+//     "isreportenter": 0,
+//     "home_url": "https://open.work.weixin.qq.com"
+// }
+/// 应用的基本信息与可见范围
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentInfo {
+    pub agentid: usize,
+    pub name: String,
+    /// 方形头像的链接
+    #[serde(default)]
+    pub square_logo_url: String,
+    #[serde(default)]
+    pub description: String,
+    /// 可见范围内的成员
+    #[serde(default)]
+    pub allow_userinfos: AllowUsers,
+    /// 可见范围内的部门
+    #[serde(default)]
+    pub allow_partys: AllowParties,
+    /// 可见范围内的标签
+    #[serde(default)]
+    pub allow_tags: AllowTags,
+    /// 是否被停用：0为启用，1为停用
+    #[serde(default)]
+    pub close: i64,
+    /// 可信域名
+    #[serde(default)]
+    pub redirect_domain: String,
+    /// 应用主页
+    #[serde(default)]
+    pub home_url: String,
+}
+
+impl AgentInfo {
+    /// 应用是否已被停用
+    pub fn is_closed(&self) -> bool {
+        self.close == 1
+    }
+
+    /// 可见范围内成员的UserID，不含通过部门或标签可见的成员
+    pub fn allowed_userids(&self) -> Vec<&str> {
+        self.allow_userinfos
+            .user
+            .iter()
+            .map(|u| u.userid.as_str())
+            .collect()
+    }
+}
+
+/// 应用可见范围内的成员
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllowUsers {
+    #[serde(default)]
+    pub user: Vec<AllowUser>,
+}
+
+/// 应用可见范围内的单个成员
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllowUser {
+    pub userid: String,
+}
+
+/// 应用可见范围内的部门
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllowParties {
+    #[serde(default)]
+    pub partyid: Vec<u64>,
+}
+
+/// 应用可见范围内的标签
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllowTags {
+    #[serde(default)]
+    pub tagid: Vec<u64>,
+}
+
+// 获取应用列表的返回结果
+// {
+//     "errcode": 0,
+//     "errmsg": "ok",
+//     "agentlist": [
+//         {
+//             "agentid": 1000005,
+//             "name": "HR助手",
+//             "square_logo_url": "https://p.qlogo.cn/bizmail/FicwmI50icF8GH9ib7rUAYR5kicLTgP265naVFQKnleqSlRhiaBx7QA9u7Q/0"
+//         }
+//     ]
+// }
+#[derive(Deserialize)]
+pub(crate) struct AgentListResponse {
+    #[serde(default)]
+    pub(crate) agentlist: Vec<AgentBrief>,
+}
+
+/// 应用列表中的单个应用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentBrief {
+    pub agentid: usize,
+    pub name: String,
+    /// 方形头像的链接
+    #[serde(default)]
+    pub square_logo_url: String,
+}
+
+// 设置应用的请求
+// {
+//     "agentid": 1000005,
+//     "report_location_flag": 0,
+//     "logo_mediaid": "xxxxx",
+//     "name": "NAME",
+//     "description": "DESC",
+//     "redirect_domain": "xxxxxx",
+//     "isreportenter": 0,
+//     "home_url": "http://www.qq.com"
+// }
+/// 应用的设置项，由`AgentSettings::builder()`构造。未设置的字段不会修改。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AgentSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logo_mediaid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    redirect_domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    home_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report_location_flag: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    isreportenter: Option<u8>,
+}
+
+impl AgentSettings {
+    pub fn builder() -> AgentSettingsBuilder {
+        AgentSettingsBuilder::default()
+    }
+}
+
+/// `AgentSettings`的构造器
+#[derive(Debug, Default)]
+pub struct AgentSettingsBuilder {
+    settings: AgentSettings,
+}
+
+impl AgentSettingsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.settings.name = Some(name.to_owned());
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.settings.description = Some(description.to_owned());
+        self
+    }
+
+    /// 应用头像，须为以`WecomAgent::upload_media()`上传的图片（`MediaType::Image`）的media_id
+    pub fn logo_mediaid(mut self, media_id: &str) -> Self {
+        self.settings.logo_mediaid = Some(media_id.to_owned());
+        self
+    }
+
+    /// 可信域名，须与应用主页的域名一致
+    pub fn redirect_domain(mut self, domain: &str) -> Self {
+        self.settings.redirect_domain = Some(domain.to_owned());
+        self
+    }
+
+    /// 应用主页，须为HTTP或HTTPS地址
+    pub fn home_url(mut self, url: &str) -> Self {
+        self.settings.home_url = Some(url.to_owned());
+        self
+    }
+
+    /// 是否开启成员进入会话时的地理位置上报
+    pub fn report_location(mut self, enabled: bool) -> Self {
+        self.settings.report_location_flag = Some(u8::from(enabled));
+        self
+    }
+
+    /// 是否上报成员进入应用的事件
+    pub fn report_enter(mut self, enabled: bool) -> Self {
+        self.settings.isreportenter = Some(u8::from(enabled));
+        self
+    }
+
+    pub fn build(self) -> AgentSettings {
+        self.settings
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_agent_info() {
+        let info: AgentInfo = serde_json::from_str(
+            r#"{
+                "errcode": 0,
+                "errmsg": "ok",
+                "agentid": 1000005,
+                "name": "HR助手",
+                "square_logo_url": "https://p.qlogo.cn/bizmail/FicwmI50icF8GH9ib7rUAYR5kicLTgP265naVFQKnleqSlRhiaBx7QA9u7Q/0",
+                "description": "HR服务与员工自助平台",
+                "allow_userinfos": {"user": [{"userid": "zhangshan"}, {"userid": "lisi"}]},
+                "allow_partys": {"partyid": [1]},
+                "allow_tags": {"tagid": [1, 2, 3]},
+                "close": 0,
+                "redirect_domain": "open.work.weixin.qq.com",
+                "report_location_flag": 0,
+                "isreportenter": 0,
+                "home_url": "https://open.work.weixin.qq.com",
+                "customized_publish_status": 1
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(info.agentid, 1000005);
+        assert_eq!(info.name, "HR助手");
+        assert_eq!(info.allowed_userids(), vec!["zhangshan", "lisi"]);
+        assert_eq!(info.allow_partys.partyid, vec![1]);
+        assert_eq!(info.allow_tags.tagid, vec![1, 2, 3]);
+        assert_eq!(info.redirect_domain, "open.work.weixin.qq.com");
+        assert!(!info.is_closed());
+
+        // 可见范围为空时接口可能省略相应字段
+        let info: AgentInfo =
+            serde_json::from_str(r#"{"agentid": 1000006, "name": "告警", "close": 1}"#).unwrap();
+        assert!(info.allowed_userids().is_empty());
+        assert!(info.allow_tags.tagid.is_empty());
+        assert!(info.is_closed());
+    }
+
+    #[test]
+    fn test_serialize_agent_settings() {
+        let settings = AgentSettings::builder()
+            .name("HR助手")
+            .report_location(true)
+            .report_enter(false)
+            .build();
+        assert_eq!(
+            serde_json::to_value(&settings).unwrap(),
+            serde_json::json!({"name": "HR助手", "report_location_flag": 1, "isreportenter": 0})
+        );
+        assert_eq!(
+            serde_json::to_value(AgentSettings::builder().build()).unwrap(),
+            serde_json::json!({})
+        );
+    }
+
+    #[test]
+    fn test_deserialize_agent_list() {
+        let response: AgentListResponse = serde_json::from_str(
+            r#"{
+                "errcode": 0,
+                "errmsg": "ok",
+                "agentlist": [
+                    {"agentid": 1000005, "name": "HR助手", "square_logo_url": "https://p.qlogo.cn/bizmail/0"},
+                    {"agentid": 1000006, "name": "告警"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(response.agentlist.len(), 2);
+        assert_eq!(response.agentlist[0].agentid, 1000005);
+        assert_eq!(response.agentlist[1].square_logo_url, "");
+    }
+}
@@ -0,0 +1,347 @@
+//! 群聊会话
+//!
+//! 应用可创建群聊会话，并向其中推送消息。
+
+use crate::message::{check_length, WecomMessage};
+use crate::WecomError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+// 群成员人数的范围
+const MIN_CHAT_MEMBERS: usize = 2;
+const MAX_CHAT_MEMBERS: usize = 2000;
+// 群名称的最大字符数
+const MAX_CHAT_NAME_CHARS: usize = 50;
+// 群聊ID的最大字节数
+const MAX_CHAT_ID_BYTES: usize = 32;
+
+// 创建群聊会话的请求
+// {
+//     "name" : "NAME",
+//     "owner" : "userid1",
+//     "userlist" : ["userid1", "userid2", "userid3"],
+//     "chatid" : "CHATID"
+// }
+/// 创建群聊会话的请求，由`CreateChatRequest::builder()`构造
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CreateChatRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner: Option<String>,
+    userlist: Vec<String>,
+    #[serde(rename = "chatid", skip_serializing_if = "Option::is_none")]
+    chat_id: Option<String>,
+}
+
+impl CreateChatRequest {
+    pub fn builder() -> CreateChatBuilder {
+        CreateChatBuilder::default()
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+}
+
+/// `CreateChatRequest`的构造器
+#[derive(Debug, Default)]
+pub struct CreateChatBuilder {
+    name: Option<String>,
+    owner: Option<String>,
+    members: Vec<String>,
+    chat_id: Option<String>,
+}
+
+impl CreateChatBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 群名称，最多50个字符
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// 群主，须为群成员之一。未指定时由服务端随机选取。
+    pub fn owner(mut self, owner: &str) -> Self {
+        self.owner = Some(owner.to_owned());
+        self
+    }
+
+    /// 群成员，2至2000人
+    pub fn members(mut self, members: Vec<&str>) -> Self {
+        self.members = members.into_iter().map(str::to_owned).collect();
+        self
+    }
+
+    /// 群聊ID，仅可包含字母、数字与下划线，最多32字节。未指定时由服务端生成。
+    pub fn chat_id(mut self, chat_id: &str) -> Self {
+        self.chat_id = Some(chat_id.to_owned());
+        self
+    }
+
+    pub fn build(self) -> Result<CreateChatRequest, WecomError> {
+        if let Some(name) = &self.name {
+            check_length("appchat.name", name, MAX_CHAT_NAME_CHARS)?;
+        }
+        let count = self.members.len();
+        if !(MIN_CHAT_MEMBERS..=MAX_CHAT_MEMBERS).contains(&count) {
+            return Err(WecomError::InvalidArgument(format!(
+                "群成员须为{MIN_CHAT_MEMBERS}至{MAX_CHAT_MEMBERS}人，实际{count}人"
+            )));
+        }
+        if let Some(owner) = &self.owner {
+            if !self.members.contains(owner) {
+                return Err(WecomError::InvalidArgument(format!(
+                    "群主{owner}不在群成员中"
+                )));
+            }
+        }
+        if let Some(chat_id) = &self.chat_id {
+            check_chat_id(chat_id)?;
+        }
+        Ok(CreateChatRequest {
+            name: self.name,
+            owner: self.owner,
+            userlist: self.members,
+            chat_id: self.chat_id,
+        })
+    }
+}
+
+// 修改群聊会话的请求
+// {
+//     "chatid" : "CHATID",
+//     "name" : "NAME",
+//     "owner" : "userid2",
+//     "add_user_list" : ["userid1", "userid2", "userid3"],
+//     "del_user_list" : ["userid3", "userid4"]
+// }
+/// 群聊会话的修改内容。未指定的字段不会修改。
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ChatUpdate {
+    /// 新的群名称，最多50个字符
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 新的群主
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// 添加的成员
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub add_user_list: Vec<String>,
+    /// 移出的成员
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub del_user_list: Vec<String>,
+}
+
+impl ChatUpdate {
+    // 检查群名称长度，以及同一成员是否既被添加又被移出
+    pub(crate) fn validate(&self) -> Result<(), WecomError> {
+        if let Some(name) = &self.name {
+            check_length("appchat.name", name, MAX_CHAT_NAME_CHARS)?;
+        }
+        if let Some(user) = self
+            .add_user_list
+            .iter()
+            .find(|u| self.del_user_list.contains(u))
+        {
+            return Err(WecomError::InvalidArgument(format!(
+                "成员{user}不可同时添加与移出"
+            )));
+        }
+        Ok(())
+    }
+}
+
+// 检查群聊ID的字符集与长度
+fn check_chat_id(chat_id: &str) -> Result<(), WecomError> {
+    let valid = !chat_id.is_empty()
+        && chat_id.len() <= MAX_CHAT_ID_BYTES
+        && chat_id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_');
+    if !valid {
+        return Err(WecomError::InvalidArgument(format!(
+            "群聊ID仅可包含字母、数字与下划线，且不超过{MAX_CHAT_ID_BYTES}字节: {chat_id}"
+        )));
+    }
+    Ok(())
+}
+
+// 群聊消息
+// 示例
+// {
+//     "chatid": "CHATID",
+//     "msgtype": "text",
+//     "text": {
+//         "content" : "你的快递已到"
+//     },
+//     "safe": 0
+// }
+// 以chatid代替应用消息的收件人与agentid
+pub(crate) fn chat_message<T>(chat_id: &str, content: T, safe: bool) -> Result<Value, WecomError>
+where
+    T: Serialize + WecomMessage,
+{
+    content.validate()?;
+    let mut msg = json!({
+        "chatid": chat_id,
+        "msgtype": content.msg_type().as_str(),
+        "safe": i64::from(safe),
+    });
+    msg[content.key()] = serde_json::to_value(content.value())?;
+    Ok(msg)
+}
+
+// 创建群聊会话的返回结果
+// {
+//     "errcode" : 0,
+//     "errmsg" : "ok",
+//     "chatid" : "CHATID"
+// }
+#[derive(Deserialize)]
+pub(crate) struct CreateChatResponse {
+    pub(crate) chatid: String,
+}
+
+// 查询群聊会话的返回结果
+// {
+//     "errcode" : 0,
+//     "errmsg" : "ok",
+//     "chat_info" : {
+//         "chatid" : "CHATID",
+//         "name" : "NAME",
+//         "owner" : "userid2",
+//         "userlist" : ["userid1", "userid2", "userid3"]
+//     }
+// }
+#[derive(Deserialize)]
+pub(crate) struct GetChatResponse {
+    pub(crate) chat_info: ChatInfo,
+}
+
+/// 群聊会话的信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatInfo {
+    #[serde(rename = "chatid")]
+    pub chat_id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub owner: String,
+    /// 群成员
+    #[serde(rename = "userlist", default)]
+    pub members: Vec<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_create_chat_request() {
+        let request = CreateChatRequest::builder()
+            .name("值班群")
+            .owner("robin")
+            .members(vec!["robin", "tom"])
+            .chat_id("oncall_42")
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "name": "值班群",
+                "owner": "robin",
+                "userlist": ["robin", "tom"],
+                "chatid": "oncall_42"
+            })
+        );
+
+        let request = CreateChatRequest::builder()
+            .members(vec!["robin", "tom"])
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({"userlist": ["robin", "tom"]})
+        );
+    }
+
+    #[test]
+    fn test_deserialize_chat_info() {
+        let raw = r#"{
+            "errcode": 0,
+            "errmsg": "ok",
+            "chat_info": {
+                "chatid": "wrkSFfCgAAtMQKg4xqDatM5C9IDHFpTw",
+                "name": "值班群",
+                "owner": "ZhangSan",
+                "userlist": ["ZhangSan", "LiSi", "WangWu"]
+            }
+        }"#;
+        let response: GetChatResponse = serde_json::from_str(raw).unwrap();
+        assert_eq!(
+            response.chat_info,
+            ChatInfo {
+                chat_id: "wrkSFfCgAAtMQKg4xqDatM5C9IDHFpTw".to_string(),
+                name: "值班群".to_string(),
+                owner: "ZhangSan".to_string(),
+                members: vec![
+                    "ZhangSan".to_string(),
+                    "LiSi".to_string(),
+                    "WangWu".to_string()
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_chat_update_validate() {
+        let update = ChatUpdate {
+            add_user_list: vec!["amy".to_string(), "tom".to_string()],
+            del_user_list: vec!["tom".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            update.validate(),
+            Err(WecomError::InvalidArgument(_))
+        ));
+        let update = ChatUpdate {
+            name: Some("群".repeat(51)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            update.validate(),
+            Err(WecomError::ContentTooLong { .. })
+        ));
+        assert!(ChatUpdate::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_create_chat_request_invalid() {
+        let builder = || CreateChatRequest::builder().members(vec!["robin", "tom"]);
+        let invalid = |result: Result<CreateChatRequest, WecomError>| {
+            matches!(result, Err(WecomError::InvalidArgument(_)))
+        };
+        assert!(invalid(
+            CreateChatRequest::builder().members(vec!["robin"]).build()
+        ));
+        let members = crate::user_ids(2001);
+        assert!(invalid(
+            CreateChatRequest::builder()
+                .members(members.iter().map(String::as_str).collect())
+                .build()
+        ));
+        assert!(invalid(builder().owner("amy").build()));
+        assert!(invalid(builder().chat_id("oncall-42").build()));
+        assert!(invalid(builder().chat_id(&"a".repeat(33)).build()));
+        assert!(builder().chat_id(&"a".repeat(32)).build().is_ok());
+        assert!(matches!(
+            builder().name(&"群".repeat(51)).build(),
+            Err(WecomError::ContentTooLong { .. })
+        ));
+    }
+}
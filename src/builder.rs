@@ -0,0 +1,369 @@
+use crate::clock::{Clock, SystemClock};
+use crate::dedup::{InMemoryDeduplicator, MessageDeduplicator};
+use crate::errcode;
+use crate::pool::RequestGauge;
+use crate::stats::AgentCounters;
+use crate::{AccessToken, WecomAgent, WecomError, DEFAULT_BASE_URL};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+// 默认的重新发送次数
+const DEFAULT_MAX_SEND_RETRIES: u32 = 1;
+
+// 空闲连接的默认保留时长
+const DEFAULT_IDLE_CONNECTION_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// 企业微信API所在的区域
+///
+/// 企业微信目前只公布了中国大陆的API域名`qyapi.weixin.qq.com`，境外服务器同样直接访问该域名。
+/// 若境外部署需要经由就近的中转服务或专线访问，可使用`Custom`指定中转地址。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum WecomRegion {
+    /// 中国大陆，`https://qyapi.weixin.qq.com`
+    #[default]
+    Mainland,
+    /// 自定义地址，与`WecomAgentBuilder::base_url()`等效
+    Custom(String),
+}
+
+impl WecomRegion {
+    /// 该区域的API地址
+    pub fn base_url(&self) -> &str {
+        match self {
+            WecomRegion::Mainland => DEFAULT_BASE_URL,
+            WecomRegion::Custom(url) => url,
+        }
+    }
+}
+
+/// `WecomAgent`的构造器
+///
+/// ```rust
+/// use wecom_agent::WecomAgentBuilder;
+///
+/// let agent = WecomAgentBuilder::new("your_corpid", "your_secret")
+///     .base_url("https://qyapi.weixin.qq.com")
+///     .build()
+///     .expect("wecom agent should be built");
+/// ```
+#[derive(Debug)]
+pub struct WecomAgentBuilder {
+    pub(crate) corp_id: String,
+    pub(crate) secret: String,
+    base_url: String,
+    max_concurrent_sends: Option<usize>,
+    idle_connection_timeout: Duration,
+    max_idle_connections: Option<usize>,
+    clock: Arc<dyn Clock>,
+    deduplicator: Option<Arc<dyn MessageDeduplicator>>,
+    max_send_retries: u32,
+    retry_codes: Vec<i64>,
+    unlicensed_as_failure: bool,
+    extra_headers: HashMap<String, String>,
+    root_certificates: Vec<reqwest::Certificate>,
+    identity: Option<reqwest::Identity>,
+    #[cfg(feature = "danger-accept-invalid-certs")]
+    accept_invalid_certs: bool,
+    #[cfg(feature = "socks-proxy")]
+    socks5_proxy: Option<Socks5Proxy>,
+    #[cfg(feature = "request-signing")]
+    signing_key: Option<String>,
+    #[cfg(feature = "opentelemetry")]
+    tracer: Option<opentelemetry::global::BoxedTracer>,
+}
+
+// SOCKS5代理的地址与认证信息
+#[cfg(feature = "socks-proxy")]
+#[derive(Debug)]
+struct Socks5Proxy {
+    addr: String,
+    auth: Option<(String, String)>,
+}
+
+#[cfg(feature = "socks-proxy")]
+impl Socks5Proxy {
+    // 地址可省略`socks5://`前缀。仅接受socks5与socks5h协议。
+    fn to_proxy(&self) -> Result<reqwest::Proxy, WecomError> {
+        let invalid = || WecomError::InvalidProxyUrl(self.addr.clone());
+        let url = if self.addr.contains("://") {
+            self.addr.clone()
+        } else {
+            format!("socks5://{}", self.addr)
+        };
+        let parsed = reqwest::Url::parse(&url).map_err(|_| invalid())?;
+        if !matches!(parsed.scheme(), "socks5" | "socks5h")
+            || parsed.host_str().is_none()
+            || parsed.port().is_none()
+        {
+            return Err(invalid());
+        }
+        let mut proxy = reqwest::Proxy::all(url).map_err(|_| invalid())?;
+        if let Some((username, password)) = &self.auth {
+            proxy = proxy.basic_auth(username, password);
+        }
+        Ok(proxy)
+    }
+}
+
+impl WecomAgentBuilder {
+    pub fn new(corp_id: &str, secret: &str) -> Self {
+        Self {
+            corp_id: String::from(corp_id),
+            secret: String::from(secret),
+            base_url: String::from(DEFAULT_BASE_URL),
+            max_concurrent_sends: None,
+            idle_connection_timeout: DEFAULT_IDLE_CONNECTION_TIMEOUT,
+            max_idle_connections: None,
+            clock: Arc::new(SystemClock),
+            deduplicator: None,
+            max_send_retries: DEFAULT_MAX_SEND_RETRIES,
+            retry_codes: vec![errcode::INVALID_ACCESS_TOKEN],
+            unlicensed_as_failure: false,
+            extra_headers: HashMap::new(),
+            root_certificates: Vec::new(),
+            identity: None,
+            #[cfg(feature = "danger-accept-invalid-certs")]
+            accept_invalid_certs: false,
+            #[cfg(feature = "socks-proxy")]
+            socks5_proxy: None,
+            #[cfg(feature = "request-signing")]
+            signing_key: None,
+            #[cfg(feature = "opentelemetry")]
+            tracer: None,
+        }
+    }
+
+    /// 设定API地址，默认为`https://qyapi.weixin.qq.com`。
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// 限制同时进行的HTTP请求数，超出的请求将排队等待。默认不限制。`n`为0时`build()`返回
+    /// `WecomError::InvalidArgument`。
+    pub fn max_concurrent_sends(mut self, n: usize) -> Self {
+        self.max_concurrent_sends = Some(n);
+        self
+    }
+
+    /// 设定API所在区域。与`base_url()`作用相同，后调用者生效。
+    pub fn region(self, region: WecomRegion) -> Self {
+        self.base_url(region.base_url())
+    }
+
+    /// 空闲连接的保留时长，默认90秒。
+    ///
+    /// 保留空闲连接可以省去后续请求重新握手的延迟，但会持续占用本地与服务端的资源。
+    /// 消息发送稀疏的场景可以调短此值，高频发送的场景则适合调长。
+    pub fn idle_connection_timeout(mut self, duration: Duration) -> Self {
+        self.idle_connection_timeout = duration;
+        self
+    }
+
+    /// 每个主机最多保留的空闲连接数，默认不限制。
+    pub fn max_idle_connections(mut self, n: usize) -> Self {
+        self.max_idle_connections = Some(n);
+        self
+    }
+
+    /// 设定时钟，默认为系统时钟。测试时可使用`MockClock`控制access token的过期。
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 设定`send_deduped()`使用的去重记录，默认为`InMemoryDeduplicator`。
+    /// 需要在进程重启后仍然去重时，使用`FileDeduplicator`或自行实现的持久化记录。
+    pub fn deduplicator(mut self, deduplicator: Arc<dyn MessageDeduplicator>) -> Self {
+        self.deduplicator = Some(deduplicator);
+        self
+    }
+
+    /// `send()`收到需重试的错误码后最多重新发送的次数，默认1次。设为0则不重试。
+    pub fn max_send_retries(mut self, n: u32) -> Self {
+        self.max_send_retries = n;
+        self
+    }
+
+    /// `send()`需要重试的错误码，默认为`[40014]`。
+    ///
+    /// access token失效类的错误码（40014、42001）在重试前先更新token；
+    /// 其余错误码按建议的时长等待后重试，如系统繁忙（-1）等待1秒。
+    pub fn retry_on_error_codes(mut self, codes: Vec<i64>) -> Self {
+        self.retry_codes = codes;
+        self
+    }
+
+    /// `send_checked()`是否将存在未激活接口许可的收件人视为失败，默认否。
+    ///
+    /// 企业开启接口许可后，消息不会送达未激活许可的成员，但errcode仍为0。
+    /// 启用后此类发送返回`WecomError::UnlicensedRecipients`。
+    pub fn treat_unlicensed_as_failure(mut self, enabled: bool) -> Self {
+        self.unlicensed_as_failure = enabled;
+        self
+    }
+
+    /// 经由SOCKS5代理访问企业微信API。`addr`形如`socks5://127.0.0.1:1080`，可省略协议前缀。
+    ///
+    /// 代理主机名在`build()`时解析。地址无效或无法解析时`build()`返回`WecomError::InvalidProxyUrl`。
+    #[cfg(feature = "socks-proxy")]
+    pub fn socks5_proxy(mut self, addr: &str) -> Self {
+        self.socks5_proxy = Some(Socks5Proxy {
+            addr: addr.to_string(),
+            auth: None,
+        });
+        self
+    }
+
+    /// 经由需要用户名与密码认证的SOCKS5代理访问企业微信API。
+    #[cfg(feature = "socks-proxy")]
+    pub fn socks5_proxy_with_auth(mut self, addr: &str, username: &str, password: &str) -> Self {
+        self.socks5_proxy = Some(Socks5Proxy {
+            addr: addr.to_string(),
+            auth: Some((username.to_string(), password.to_string())),
+        });
+        self
+    }
+
+    /// 为每个请求附加的请求头，例如经由企业内部API网关访问时所需的认证信息。多次调用时合并。
+    ///
+    /// 请求头名称或取值不合法时`build()`返回`WecomError::InvalidArgument`。
+    /// 同名的请求头以各接口自身设定的为准，如上传素材时的`Content-Type`。
+    pub fn extra_headers(mut self, headers: HashMap<String, String>) -> Self {
+        self.extra_headers.extend(headers);
+        self
+    }
+
+    /// 经由企业内部API网关访问，相当于同时调用`base_url()`与`extra_headers()`。
+    /// `extra_headers`附加于包括获取access token在内的每个请求。
+    pub fn custom_gateway(self, base_url: &str, extra_headers: HashMap<String, String>) -> Self {
+        self.base_url(base_url).extra_headers(extra_headers)
+    }
+
+    /// 信任额外的根证书，例如会重新签发TLS流量的企业HTTPS代理所用的CA证书。多次调用时逐一添加。
+    ///
+    /// 证书不是有效的PEM格式时返回`WecomError::TlsError`。
+    pub fn add_root_certificate(mut self, cert_pem: &[u8]) -> Result<Self, WecomError> {
+        let cert = reqwest::Certificate::from_pem(cert_pem)
+            .map_err(|e| WecomError::TlsError(format!("根证书无效: {e}")))?;
+        self.root_certificates.push(cert);
+        Ok(self)
+    }
+
+    /// 以客户端证书进行双向TLS认证。
+    ///
+    /// 仅用于要求客户端证书的私有化部署，访问企业微信公有云API时无需设置。
+    ///
+    /// `identity_pem`为PEM格式的证书（可附带中间证书），`key_pem`为PEM格式的PKCS#8私钥。
+    /// 证书或私钥无效时返回`WecomError::TlsError`。多次调用时以最后一次为准。
+    pub fn client_certificate(
+        mut self,
+        identity_pem: &[u8],
+        key_pem: &[u8],
+    ) -> Result<Self, WecomError> {
+        let identity = reqwest::Identity::from_pkcs8_pem(identity_pem, key_pem)
+            .map_err(|e| WecomError::TlsError(format!("客户端证书无效: {e}")))?;
+        self.identity = Some(identity);
+        Ok(self)
+    }
+
+    /// 不校验服务端的TLS证书，以便测试时访问使用自签名证书的模拟服务器。
+    ///
+    /// **NEVER enable this in production code.** 关闭证书校验后，任何人都可冒充企业微信服务器。
+    /// 启用时`build()`将记录一条警告。
+    #[cfg(feature = "danger-accept-invalid-certs")]
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    /// 为每个请求签名，供要求请求签名的私有化部署使用。
+    ///
+    /// 签名为`hmac-sha256(timestamp + nonce + body, secret_key)`的小写十六进制，与时间戳、随机串分别通过
+    /// `X-WeCom-Signature`、`X-WeCom-Timestamp`与`X-WeCom-Nonce`请求头发送。流式上传的请求体按空内容签名。
+    #[cfg(feature = "request-signing")]
+    pub fn request_signing(mut self, secret_key: String) -> Self {
+        self.signing_key = Some(secret_key);
+        self
+    }
+
+    /// 为`send()`与`update_token()`的每次调用生成OpenTelemetry span。
+    #[cfg(feature = "opentelemetry")]
+    pub fn with_tracer(mut self, tracer: opentelemetry::global::BoxedTracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    pub fn build(self) -> Result<WecomAgent, WecomError> {
+        if self.max_concurrent_sends == Some(0) {
+            return Err(WecomError::InvalidArgument(
+                "max_concurrent_sends须大于0".to_string(),
+            ));
+        }
+        let mut client_builder =
+            reqwest::Client::builder().pool_idle_timeout(self.idle_connection_timeout);
+        if let Some(n) = self.max_idle_connections {
+            client_builder = client_builder.pool_max_idle_per_host(n);
+        }
+        if !self.extra_headers.is_empty() {
+            client_builder = client_builder.default_headers(header_map(&self.extra_headers)?);
+        }
+        for cert in self.root_certificates {
+            client_builder = client_builder.add_root_certificate(cert);
+        }
+        if let Some(identity) = self.identity {
+            client_builder = client_builder.identity(identity);
+        }
+        #[cfg(feature = "danger-accept-invalid-certs")]
+        if self.accept_invalid_certs {
+            tracing::warn!(
+                "TLS certificate verification is disabled. NEVER enable this in production code"
+            );
+            client_builder = client_builder.danger_accept_invalid_certs(true);
+        }
+        #[cfg(feature = "socks-proxy")]
+        if let Some(proxy) = &self.socks5_proxy {
+            client_builder = client_builder.proxy(proxy.to_proxy()?);
+        }
+        let client = client_builder.build()?;
+        let deduplicator = self
+            .deduplicator
+            .unwrap_or_else(|| Arc::new(InMemoryDeduplicator::with_clock(self.clock.clone())));
+        Ok(WecomAgent {
+            corp_id: self.corp_id,
+            secret: std::sync::RwLock::new(self.secret),
+            access_token: RwLock::new(AccessToken::new(self.clock.clone())),
+            client,
+            base_url: self.base_url,
+            clock: self.clock,
+            gauge: RequestGauge::new(self.max_concurrent_sends),
+            counters: AgentCounters::default(),
+            media_cache: None,
+            deduplicator,
+            healthy: std::sync::atomic::AtomicBool::new(true),
+            max_send_retries: self.max_send_retries,
+            retry_codes: self.retry_codes,
+            unlicensed_as_failure: self.unlicensed_as_failure,
+            #[cfg(feature = "request-signing")]
+            signer: self.signing_key.map(crate::signing::RequestSigner::new),
+            #[cfg(feature = "opentelemetry")]
+            tracer: self.tracer,
+        })
+    }
+}
+
+// 将附加请求头转换为HeaderMap
+fn header_map(headers: &HashMap<String, String>) -> Result<HeaderMap, WecomError> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let invalid = || WecomError::InvalidArgument(format!("请求头不合法: {name}"));
+        map.insert(
+            HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?,
+            HeaderValue::from_str(value).map_err(|_| invalid())?,
+        );
+    }
+    Ok(map)
+}
@@ -0,0 +1,54 @@
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// 时钟。access token的过期判断与更新频率控制都基于它读取当前时刻，测试时可替换为`MockClock`。
+pub trait Clock: Send + Sync + Debug {
+    fn now(&self) -> SystemTime;
+}
+
+/// 系统时钟
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// 手动拨动的时钟，用于测试
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    current: Arc<Mutex<SystemTime>>,
+}
+
+impl MockClock {
+    pub fn new(start: SystemTime) -> Self {
+        Self {
+            current: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    /// 将时钟拨快`duration`
+    pub fn advance(&self, duration: Duration) {
+        *self.current.lock().expect("Clock lock poisoned") += duration;
+    }
+
+    /// 将时钟设为`time`
+    pub fn set(&self, time: SystemTime) {
+        *self.current.lock().expect("Clock lock poisoned") = time;
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new(SystemTime::now())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        *self.current.lock().expect("Clock lock poisoned")
+    }
+}
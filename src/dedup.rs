@@ -0,0 +1,192 @@
+use crate::clock::{Clock, SystemClock};
+use crate::{MsgSendResponse, WecomError};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// 消息去重记录，供`WecomAgent::send_deduped()`判断消息是否已发送过。
+///
+/// 企业微信的`enable_duplicate_check`仅在服务端的时间窗口内生效，且无法覆盖进程重启后的重复发送。
+/// 实现持久化的去重记录，即可在重启后跳过已发送的消息。
+pub trait MessageDeduplicator: Send + Sync + Debug {
+    /// `key`是否已标记为已发送且尚未过期
+    fn was_sent(&self, key: &str) -> bool;
+    /// 将`key`标记为已发送，`ttl`后过期
+    fn mark_sent(&self, key: &str, ttl: Duration);
+}
+
+/// `WecomAgent::send_deduped()`的结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum DedupResult {
+    /// 去重键在有效期内已发送过，本次未发送
+    AlreadySent,
+    /// 已发送，内容为发送结果
+    Sent(MsgSendResponse),
+}
+
+/// 保存在内存中的去重记录，进程退出后失效。未设定时`WecomAgent`默认使用它。
+#[derive(Debug)]
+pub struct InMemoryDeduplicator {
+    entries: Mutex<HashMap<String, SystemTime>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl InMemoryDeduplicator {
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// 以`clock`判断记录是否过期，用于测试
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            clock,
+        }
+    }
+}
+
+impl Default for InMemoryDeduplicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MessageDeduplicator for InMemoryDeduplicator {
+    fn was_sent(&self, key: &str) -> bool {
+        let entries = self.entries.lock().expect("Deduplicator lock poisoned");
+        entries
+            .get(key)
+            .is_some_and(|expires_at| *expires_at > self.clock.now())
+    }
+
+    fn mark_sent(&self, key: &str, ttl: Duration) {
+        let now = self.clock.now();
+        let mut entries = self.entries.lock().expect("Deduplicator lock poisoned");
+        entries.retain(|_, expires_at| *expires_at > now);
+        entries.insert(key.to_owned(), now + ttl);
+    }
+}
+
+/// 保存在本地JSON文件中的去重记录，进程重启后仍然有效。
+///
+/// 文件内容为去重键到过期时刻（Unix时间戳，秒）的映射。每次标记后整体写回文件，
+/// 写入失败时仅记录警告，不影响已发出的消息。
+#[derive(Debug)]
+pub struct FileDeduplicator {
+    path: PathBuf,
+    entries: Mutex<HashMap<String, u64>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl FileDeduplicator {
+    /// 以`path`处的文件保存去重记录。文件不存在时视为无记录，首次标记时创建。
+    /// 文件无法读取或内容无效时返回错误。
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, WecomError> {
+        Self::with_clock(path, Arc::new(SystemClock))
+    }
+
+    /// 以`clock`判断记录是否过期，用于测试
+    pub fn with_clock(path: impl AsRef<Path>, clock: Arc<dyn Clock>) -> Result<Self, WecomError> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path,
+            entries: Mutex::new(entries),
+            clock,
+        })
+    }
+
+    fn now_secs(&self) -> u64 {
+        self.clock
+            .now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs())
+    }
+
+    // 先写入临时文件再重命名，以免写入中途退出时损坏原有记录
+    fn persist(&self, entries: &HashMap<String, u64>) -> Result<(), WecomError> {
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(entries)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+impl MessageDeduplicator for FileDeduplicator {
+    fn was_sent(&self, key: &str) -> bool {
+        let entries = self.entries.lock().expect("Deduplicator lock poisoned");
+        entries
+            .get(key)
+            .is_some_and(|expires_at| *expires_at > self.now_secs())
+    }
+
+    fn mark_sent(&self, key: &str, ttl: Duration) {
+        let now = self.now_secs();
+        let mut entries = self.entries.lock().expect("Deduplicator lock poisoned");
+        entries.retain(|_, expires_at| *expires_at > now);
+        entries.insert(key.to_owned(), now + ttl.as_secs());
+        if let Err(e) = self.persist(&entries) {
+            warn!(
+                "Failed to persist dedup record to {}: {e}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MockClock;
+
+    #[test]
+    fn test_in_memory_deduplicator() {
+        let clock = Arc::new(MockClock::new(
+            UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        ));
+        let dedup = InMemoryDeduplicator::with_clock(clock.clone());
+        assert!(!dedup.was_sent("alert-1"));
+        dedup.mark_sent("alert-1", Duration::from_secs(60));
+        assert!(dedup.was_sent("alert-1"));
+        assert!(!dedup.was_sent("alert-2"));
+        clock.advance(Duration::from_secs(60));
+        assert!(!dedup.was_sent("alert-1"));
+    }
+
+    #[test]
+    fn test_file_deduplicator_survives_restart() {
+        let path =
+            std::env::temp_dir().join(format!("wecom-agent-dedup-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let clock = Arc::new(MockClock::new(
+            UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        ));
+
+        let dedup = FileDeduplicator::with_clock(&path, clock.clone()).unwrap();
+        assert!(!dedup.was_sent("alert-1"));
+        dedup.mark_sent("alert-1", Duration::from_secs(60));
+        dedup.mark_sent("alert-2", Duration::from_secs(3600));
+        drop(dedup);
+
+        // 重新加载后记录仍然有效
+        let dedup = FileDeduplicator::with_clock(&path, clock.clone()).unwrap();
+        assert!(dedup.was_sent("alert-1"));
+        clock.advance(Duration::from_secs(60));
+        assert!(!dedup.was_sent("alert-1"));
+        assert!(dedup.was_sent("alert-2"));
+
+        std::fs::write(&path, b"not json").unwrap();
+        assert!(matches!(
+            FileDeduplicator::new(&path),
+            Err(WecomError::Json { .. })
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
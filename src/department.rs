@@ -0,0 +1,252 @@
+//! 通讯录部门
+//!
+//! 部门列表以平铺形式返回，可通过[`build_tree()`]按`parentid`组装为部门树。
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// 获取部门列表的返回结果
+// {
+//     "errcode": 0,
+//     "errmsg": "ok",
+//     "department": [
+//         {
+//             "id": 2,
+//             "name": "广州研发中心",
+//             "name_en": "RDGZ",
+//             "department_leader": ["zhangsan", "lisi"],
+//             "parentid": 1,
+//             "order": 10
+//         }
+//     ]
+// }
+#[derive(Deserialize)]
+pub(crate) struct DepartmentListResponse {
+    #[serde(default)]
+    pub(crate) department: Vec<Department>,
+}
+
+// 获取子部门ID列表的返回结果
+// {
+//     "errcode": 0,
+//     "errmsg": "ok",
+//     "department_id": [
+//         {
+//             "id": 2,
+//             "parentid": 1,
+//             "order": 10
+//         }
+//     ]
+// }
+#[derive(Deserialize)]
+pub(crate) struct SimpleDepartmentListResponse {
+    #[serde(default)]
+    pub(crate) department_id: Vec<SimpleDepartment>,
+}
+
+/// 部门信息。名称等字段是否返回取决于应用的通讯录权限。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Department {
+    pub id: u64,
+    pub name: Option<String>,
+    /// 英文名称
+    pub name_en: Option<String>,
+    /// 部门负责人的userid
+    pub department_leader: Option<Vec<String>>,
+    /// 父部门ID，根部门为0
+    pub parentid: u64,
+    /// 在父部门中的次序值，值大的排序靠前
+    #[serde(default)]
+    pub order: u64,
+}
+
+/// 部门ID与父部门ID，不含名称
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimpleDepartment {
+    pub id: u64,
+    /// 父部门ID，根部门为0
+    pub parentid: u64,
+    /// 在父部门中的次序值，值大的排序靠前
+    #[serde(default)]
+    pub order: u64,
+}
+
+/// 可组装为部门树的部门信息
+pub trait DepartmentItem {
+    fn id(&self) -> u64;
+    fn parent_id(&self) -> u64;
+    fn order(&self) -> u64;
+}
+
+impl DepartmentItem for Department {
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn parent_id(&self) -> u64 {
+        self.parentid
+    }
+
+    fn order(&self) -> u64 {
+        self.order
+    }
+}
+
+impl DepartmentItem for SimpleDepartment {
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn parent_id(&self) -> u64 {
+        self.parentid
+    }
+
+    fn order(&self) -> u64 {
+        self.order
+    }
+}
+
+/// 部门树的节点
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepartmentNode<T> {
+    pub department: T,
+    /// 子部门，按次序值从大到小排列
+    pub children: Vec<DepartmentNode<T>>,
+}
+
+impl<T: DepartmentItem> DepartmentNode<T> {
+    /// 本部门及全部下级部门的ID，先序遍历
+    pub fn ids(&self) -> Vec<u64> {
+        let mut ids = vec![self.department.id()];
+        for child in &self.children {
+            ids.extend(child.ids());
+        }
+        ids
+    }
+}
+
+/// 将平铺的部门列表组装为部门树，返回各棵树的根节点。
+///
+/// 父部门不在列表中的部门视为根节点，例如只获取了某个部门及其子部门时，该部门即为根节点。
+/// 同级部门按次序值从大到小排列。
+pub fn build_tree<T: DepartmentItem>(departments: Vec<T>) -> Vec<DepartmentNode<T>> {
+    let ids: HashSet<u64> = departments.iter().map(|d| d.id()).collect();
+    let mut roots = Vec::new();
+    let mut children: HashMap<u64, Vec<T>> = HashMap::new();
+    for department in departments {
+        if ids.contains(&department.parent_id()) && department.parent_id() != department.id() {
+            children
+                .entry(department.parent_id())
+                .or_default()
+                .push(department);
+        } else {
+            roots.push(department);
+        }
+    }
+    attach(roots, &mut children)
+}
+
+// 为各部门挂载子部门，并按次序值排序
+fn attach<T: DepartmentItem>(
+    mut departments: Vec<T>,
+    children: &mut HashMap<u64, Vec<T>>,
+) -> Vec<DepartmentNode<T>> {
+    departments.sort_by_key(|d| std::cmp::Reverse(d.order()));
+    departments
+        .into_iter()
+        .map(|department| {
+            let sub = children.remove(&department.id()).unwrap_or_default();
+            DepartmentNode {
+                children: attach(sub, children),
+                department,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_departments() {
+        let raw = r#"{
+            "errcode": 0,
+            "errmsg": "ok",
+            "department": [
+                {
+                    "id": 2,
+                    "name": "广州研发中心",
+                    "name_en": "RDGZ",
+                    "department_leader": ["zhangsan", "lisi"],
+                    "parentid": 1,
+                    "order": 10
+                },
+                {
+                    "id": 3,
+                    "name": "邮箱产品部",
+                    "name_en": "mail",
+                    "department_leader": ["lisi", "wangwu"],
+                    "parentid": 2,
+                    "order": 40
+                }
+            ]
+        }"#;
+        let response: DepartmentListResponse = serde_json::from_str(raw).unwrap();
+        assert_eq!(
+            response.department[0],
+            Department {
+                id: 2,
+                name: Some("广州研发中心".to_string()),
+                name_en: Some("RDGZ".to_string()),
+                department_leader: Some(vec!["zhangsan".to_string(), "lisi".to_string()]),
+                parentid: 1,
+                order: 10,
+            }
+        );
+
+        let raw = r#"{
+            "errcode": 0,
+            "errmsg": "ok",
+            "department_id": [
+                {"id": 2, "parentid": 1, "order": 10},
+                {"id": 3, "parentid": 2, "order": 40}
+            ]
+        }"#;
+        let response: SimpleDepartmentListResponse = serde_json::from_str(raw).unwrap();
+        assert_eq!(
+            response.department_id[1],
+            SimpleDepartment {
+                id: 3,
+                parentid: 2,
+                order: 40
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_tree() {
+        let department = |id, parentid, order| SimpleDepartment {
+            id,
+            parentid,
+            order,
+        };
+        let tree = build_tree(vec![
+            department(3, 2, 40),
+            department(1, 0, 100),
+            department(2, 1, 10),
+            department(4, 1, 20),
+            department(5, 2, 50),
+        ]);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].ids(), [1, 4, 2, 5, 3]);
+        let children: Vec<u64> = tree[0].children.iter().map(|n| n.department.id).collect();
+        assert_eq!(children, [4, 2]);
+
+        // 父部门不在列表中的部门视为根节点
+        let tree = build_tree(vec![department(3, 2, 40), department(5, 2, 50)]);
+        let roots: Vec<u64> = tree.iter().map(|n| n.department.id).collect();
+        assert_eq!(roots, [5, 3]);
+        assert!(build_tree::<SimpleDepartment>(Vec::new()).is_empty());
+    }
+}
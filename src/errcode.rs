@@ -0,0 +1,108 @@
+//! 企业微信全局错误码
+//!
+//! 这里只收录了常见的错误码。完整列表参见企业微信开发者文档的“全局错误码”一节。
+
+use std::time::Duration;
+
+/// 系统繁忙
+pub const SYSTEM_BUSY: i64 = -1;
+/// 不合法的secret参数
+pub const INVALID_CREDENTIAL: i64 = 40001;
+/// 不合法的CorpID
+pub const INVALID_CORP_ID: i64 = 40013;
+/// 不合法的access_token
+pub const INVALID_ACCESS_TOKEN: i64 = 40014;
+/// 不合法的agentid
+pub const INVALID_AGENT_ID: i64 = 40056;
+/// 缺少access_token参数
+pub const MISSING_ACCESS_TOKEN: i64 = 41001;
+/// access_token已过期
+pub const ACCESS_TOKEN_EXPIRED: i64 = 42001;
+/// 接口调用超过限制
+pub const API_FREQ_OUT_OF_LIMIT: i64 = 45009;
+/// API调用太频繁
+pub const API_TOO_FREQUENT: i64 = 45011;
+/// 不存在的成员
+pub const MEMBER_NOT_FOUND: i64 = 46004;
+/// API接口无权限调用
+pub const API_UNAUTHORIZED: i64 = 48002;
+/// 指定的成员/部门/标签参数无权限
+pub const NO_PRIVILEGE: i64 = 60011;
+/// 不安全的访问IP
+pub const IP_NOT_ALLOWED: i64 = 60020;
+/// UserID不存在
+pub const USER_NOT_FOUND: i64 = 60111;
+/// 收件人全部非法或无权限
+pub const NO_VALID_RECIPIENT: i64 = 81013;
+/// 群聊会话不存在
+pub const CHAT_NOT_FOUND: i64 = 86003;
+/// 无权限操作指定的应用
+pub const NO_AGENT_PRIVILEGE: i64 = 301002;
+
+// 错误码映射表
+const TABLE: &[(i64, &str)] = &[
+    (SYSTEM_BUSY, "系统繁忙"),
+    (INVALID_CREDENTIAL, "不合法的secret参数"),
+    (INVALID_CORP_ID, "不合法的CorpID"),
+    (INVALID_ACCESS_TOKEN, "不合法的access_token"),
+    (INVALID_AGENT_ID, "不合法的agentid"),
+    (MISSING_ACCESS_TOKEN, "缺少access_token参数"),
+    (ACCESS_TOKEN_EXPIRED, "access_token已过期"),
+    (API_FREQ_OUT_OF_LIMIT, "接口调用超过限制"),
+    (API_TOO_FREQUENT, "API调用太频繁，请稍候再试"),
+    (MEMBER_NOT_FOUND, "不存在的成员"),
+    (API_UNAUTHORIZED, "API接口无权限调用"),
+    (NO_PRIVILEGE, "指定的成员/部门/标签参数无权限"),
+    (IP_NOT_ALLOWED, "不安全的访问IP"),
+    (USER_NOT_FOUND, "UserID不存在"),
+    (NO_VALID_RECIPIENT, "UserID、部门ID、标签ID全部非法或无权限"),
+    (CHAT_NOT_FOUND, "群聊会话不存在"),
+    (NO_AGENT_PRIVILEGE, "无权限操作指定的应用"),
+];
+
+// 可重试的错误码及建议的等待时长。未列出的错误码不可重试。
+const RETRY_POLICY: &[(i64, Option<Duration>)] = &[
+    (SYSTEM_BUSY, Some(Duration::from_secs(1))),
+    (INVALID_ACCESS_TOKEN, None),
+    (ACCESS_TOKEN_EXPIRED, None),
+    (API_FREQ_OUT_OF_LIMIT, Some(Duration::from_secs(60))),
+    (API_TOO_FREQUENT, Some(Duration::from_secs(1))),
+];
+
+// 错误码的重试建议。不可重试时返回None，可重试时返回建议的等待时长。
+pub(crate) fn retry_policy(code: i64) -> Option<Option<Duration>> {
+    RETRY_POLICY
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, wait)| *wait)
+}
+
+/// 错误码的中文描述，便于日志输出。未收录的错误码返回None。
+pub fn describe(code: i64) -> Option<&'static str> {
+    TABLE.iter().find(|(c, _)| *c == code).map(|(_, d)| *d)
+}
+
+// access token失效，刷新后可重试
+pub(crate) fn is_token_invalid(code: i64) -> bool {
+    matches!(code, INVALID_ACCESS_TOKEN | ACCESS_TOKEN_EXPIRED)
+}
+
+// 触发了频率限制
+pub(crate) fn is_rate_limited(code: i64) -> bool {
+    matches!(code, API_FREQ_OUT_OF_LIMIT | API_TOO_FREQUENT)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe(40014), Some("不合法的access_token"));
+        assert_eq!(
+            describe(81013),
+            Some("UserID、部门ID、标签ID全部非法或无权限")
+        );
+        assert_eq!(describe(123456), None);
+    }
+}
@@ -1,22 +1,761 @@
+use crate::errcode;
+use serde_json::Value;
 use std::error::Error as StdError;
 use std::fmt;
+use std::net::IpAddr;
+use std::time::Duration;
 
-#[derive(Debug, Clone)]
-pub struct Error {
-    code: i64,
-    text: String,
+/// 调用企业微信API过程中可能出现的错误
+#[derive(Debug)]
+pub enum WecomError {
+    /// 企业微信接口返回了非0的errcode
+    Api {
+        code: i64,
+        msg: String,
+        context: Option<Box<RequestContext>>,
+    },
+    /// HTTP请求失败
+    Http {
+        source: reqwest::Error,
+        context: Option<Box<RequestContext>>,
+    },
+    /// JSON序列化或反序列化失败
+    Json {
+        source: serde_json::Error,
+        context: Option<Box<RequestContext>>,
+    },
+    /// 访问IP不在企业微信后台的可信IP列表中（60020）。`your_ip`为从错误信息中解析出的出口IP。
+    IpNotAllowed {
+        your_ip: Option<String>,
+        context: Option<Box<RequestContext>>,
+    },
+    /// 收件人全部非法或无权限（81013）。各字段为企业微信返回的无效成员、部门与标签。
+    AllRecipientsInvalid {
+        users: Vec<String>,
+        parties: Vec<String>,
+        tags: Vec<String>,
+        context: Option<Box<RequestContext>>,
+    },
+    /// 发送未能在`SendConfig::timeout`规定的时限内完成
+    Timeout {
+        timeout: Duration,
+        context: Option<Box<RequestContext>>,
+    },
+    /// 消息已发出，但有收件人未激活接口许可而未能送达。仅在启用
+    /// `WecomAgentBuilder::treat_unlicensed_as_failure()`后由`send_checked()`返回。
+    UnlicensedRecipients {
+        users: Vec<String>,
+        msgid: Option<String>,
+        context: Option<Box<RequestContext>>,
+    },
+    /// access token更新过于频繁。`since`为距上次更新的时长，`backoff`为要求的最短更新间隔。
+    TokenRefreshThrottled { since: Duration, backoff: Duration },
+    /// 消息构造失败
+    Build(String),
+    /// 读写本地文件失败
+    Io(std::io::Error),
+    /// 参数不合法，如图文消息的文章数量超出范围
+    InvalidArgument(String),
+    /// 内容超出长度上限。`actual`与`limit`的单位由`unit`指明。
+    ContentTooLong {
+        field: &'static str,
+        actual: usize,
+        limit: usize,
+        unit: LengthUnit,
+    },
+    /// 代理地址无效。内容为传入的代理地址，不含认证信息。
+    InvalidProxyUrl(String),
+    /// 链接无效或不是HTTPS地址，如图文消息的`url`与`picurl`。内容为传入的链接。
+    InvalidUrl(String),
+    /// TLS配置无效，如根证书不是有效的PEM格式
+    TlsError(String),
+    /// 无法从Vault读取企业微信凭据，如Vault无法连接、拒绝请求或密钥缺少字段
+    VaultError(String),
+    /// 接口返回成功，但响应中缺少必需的字段，如上传素材的响应中没有media_id
+    MissingField {
+        field: &'static str,
+        context: Option<Box<RequestContext>>,
+    },
 }
 
-impl Error {
-    pub fn new(code: i64, text: String) -> Self {
-        Self { code, text }
+/// 长度的计量单位。企业微信对不同字段分别按字节或字符限制长度。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthUnit {
+    /// UTF-8编码后的字节数，中文字符占3个字节
+    Bytes,
+    /// 字符数
+    Chars,
+}
+
+impl fmt::Display for LengthUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LengthUnit::Bytes => write!(f, "字节"),
+            LengthUnit::Chars => write!(f, "字符"),
+        }
+    }
+}
+
+/// 出错请求的上下文。不包含access token与收件人ID等敏感信息。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestContext {
+    path: String,
+    msg_type: Option<String>,
+    agent_id: Option<i64>,
+    recipient_count: Option<usize>,
+}
+
+impl RequestContext {
+    pub(crate) fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    // 从应用消息中提取msgtype、agentid与收件人数量
+    pub(crate) fn for_message(path: &str, msg: &Value) -> Self {
+        let recipient_count = ["touser", "toparty", "totag"]
+            .iter()
+            .filter_map(|key| msg.get(key).and_then(|v| v.as_str()))
+            .flat_map(|ids| ids.split('|'))
+            .filter(|id| !id.trim().is_empty())
+            .count();
+        Self {
+            path: path.to_string(),
+            msg_type: msg
+                .get("msgtype")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            agent_id: msg.get("agentid").and_then(|v| v.as_i64()),
+            recipient_count: Some(recipient_count),
+        }
+    }
+
+    /// 接口路径，如`/cgi-bin/message/send`
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn msg_type(&self) -> Option<&str> {
+        self.msg_type.as_deref()
+    }
+
+    pub fn agent_id(&self) -> Option<i64> {
+        self.agent_id
+    }
+
+    /// 收件人（成员、部门与标签）的数量
+    pub fn recipient_count(&self) -> Option<usize> {
+        self.recipient_count
     }
 }
 
-impl fmt::Display for Error {
+impl fmt::Display for RequestContext {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Error code: {}, {}", self.code, self.text)
+        write!(f, "path={}", self.path)?;
+        if let Some(msg_type) = &self.msg_type {
+            write!(f, ", msgtype={msg_type}")?;
+        }
+        if let Some(agent_id) = self.agent_id {
+            write!(f, ", agentid={agent_id}")?;
+        }
+        if let Some(n) = self.recipient_count {
+            write!(f, ", recipients={n}")?;
+        }
+        Ok(())
     }
 }
 
-impl StdError for Error {}
+impl WecomError {
+    // 本地错误的错误码。企业微信的全局错误码不使用这些值。
+    /// HTTP请求失败
+    pub const CODE_HTTP: i64 = -10;
+    /// JSON处理失败
+    pub const CODE_JSON: i64 = -11;
+    /// Access token更新过于频繁
+    pub const CODE_TOKEN_REFRESH_THROTTLED: i64 = -9;
+    /// 请求超时
+    pub const CODE_TIMEOUT: i64 = -13;
+    /// 参数不合法
+    pub const CODE_INVALID_ARGUMENT: i64 = -14;
+    /// 内容超出长度上限
+    pub const CODE_CONTENT_TOO_LONG: i64 = -15;
+    /// 收件人未激活接口许可
+    pub const CODE_UNLICENSED_RECIPIENTS: i64 = -16;
+    /// 代理地址无效
+    pub const CODE_INVALID_PROXY_URL: i64 = -12;
+    /// 读写本地文件失败
+    pub const CODE_IO: i64 = -17;
+    /// 链接无效
+    pub const CODE_INVALID_URL: i64 = -18;
+    /// TLS配置无效
+    pub const CODE_TLS: i64 = -19;
+    /// 无法从Vault读取凭据
+    pub const CODE_VAULT: i64 = -20;
+    /// 响应中缺少必需的字段
+    pub const CODE_MISSING_FIELD: i64 = -21;
+    /// 消息构造失败
+    pub const CODE_BUILD: i64 = -999;
+
+    // 由接口返回的errcode与errmsg构造错误，部分错误码映射为专门的变体
+    pub(crate) fn api(code: i64, msg: String) -> Self {
+        match code {
+            errcode::IP_NOT_ALLOWED => WecomError::IpNotAllowed {
+                your_ip: parse_ip(&msg),
+                context: None,
+            },
+            _ => WecomError::Api {
+                code,
+                msg,
+                context: None,
+            },
+        }
+    }
+
+    /// 错误码。企业微信接口错误返回其errcode，本地错误返回`CODE_*`常量。
+    pub fn code(&self) -> i64 {
+        match self {
+            WecomError::Api { code, .. } => *code,
+            WecomError::Http { .. } => Self::CODE_HTTP,
+            WecomError::Json { .. } => Self::CODE_JSON,
+            WecomError::IpNotAllowed { .. } => errcode::IP_NOT_ALLOWED,
+            WecomError::AllRecipientsInvalid { .. } => errcode::NO_VALID_RECIPIENT,
+            WecomError::Timeout { .. } => Self::CODE_TIMEOUT,
+            WecomError::UnlicensedRecipients { .. } => Self::CODE_UNLICENSED_RECIPIENTS,
+            WecomError::TokenRefreshThrottled { .. } => Self::CODE_TOKEN_REFRESH_THROTTLED,
+            WecomError::Build(_) => Self::CODE_BUILD,
+            WecomError::Io(_) => Self::CODE_IO,
+            WecomError::InvalidArgument(_) => Self::CODE_INVALID_ARGUMENT,
+            WecomError::ContentTooLong { .. } => Self::CODE_CONTENT_TOO_LONG,
+            WecomError::InvalidProxyUrl(_) => Self::CODE_INVALID_PROXY_URL,
+            WecomError::InvalidUrl(_) => Self::CODE_INVALID_URL,
+            WecomError::TlsError(_) => Self::CODE_TLS,
+            WecomError::VaultError(_) => Self::CODE_VAULT,
+            WecomError::MissingField { .. } => Self::CODE_MISSING_FIELD,
+        }
+    }
+
+    /// access token不合法或已过期（40014、42001）
+    pub fn is_token_invalid(&self) -> bool {
+        errcode::is_token_invalid(self.code())
+    }
+
+    /// 接口调用触发频率限制（45009、45011）
+    pub fn is_rate_limited(&self) -> bool {
+        errcode::is_rate_limited(self.code())
+    }
+
+    /// 收件人全部非法或无权限（81013）
+    pub fn is_no_valid_recipient(&self) -> bool {
+        self.code() == errcode::NO_VALID_RECIPIENT
+    }
+
+    /// 访问IP不在企业微信后台的可信IP列表中（60020）
+    pub fn is_ip_not_allowed(&self) -> bool {
+        self.code() == errcode::IP_NOT_ALLOWED
+    }
+
+    /// 无权限访问指定的成员、部门或标签（60011）
+    pub fn is_permission_denied(&self) -> bool {
+        self.code() == errcode::NO_PRIVILEGE
+    }
+
+    /// 当前secret无权调用该接口（48002），如以普通应用的secret获取应用列表
+    pub fn is_api_unauthorized(&self) -> bool {
+        self.code() == errcode::API_UNAUTHORIZED
+    }
+
+    /// 无权访问指定的应用（60011或301002），如`agent_id`与secret不属于同一应用
+    pub fn is_agent_access_denied(&self) -> bool {
+        matches!(
+            self.code(),
+            errcode::NO_PRIVILEGE | errcode::NO_AGENT_PRIVILEGE
+        )
+    }
+
+    /// 群聊会话不存在（86003）
+    pub fn is_chat_not_found(&self) -> bool {
+        self.code() == errcode::CHAT_NOT_FOUND
+    }
+
+    /// 成员不存在（60111或46004），如openid不属于企业成员
+    pub fn is_user_not_found(&self) -> bool {
+        matches!(
+            self.code(),
+            errcode::USER_NOT_FOUND | errcode::MEMBER_NOT_FOUND
+        )
+    }
+
+    /// 是否值得重试：网络超时或连接失败、频率限制、系统繁忙、access token失效等返回true，
+    /// 凭据错误、收件人无效、无权限等返回false。
+    pub fn retryable(&self) -> bool {
+        match self {
+            WecomError::Http { source, .. } => source.is_timeout() || source.is_connect(),
+            WecomError::Timeout { .. } | WecomError::TokenRefreshThrottled { .. } => true,
+            WecomError::Api { code, .. } => errcode::retry_policy(*code).is_some(),
+            _ => false,
+        }
+    }
+
+    /// 建议的重试等待时长。不可重试或无需等待时返回None。
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            WecomError::TokenRefreshThrottled { since, backoff } => {
+                Some(backoff.saturating_sub(*since))
+            }
+            WecomError::Api { code, .. } => errcode::retry_policy(*code).flatten(),
+            _ => None,
+        }
+    }
+
+    /// 出错请求的上下文。本地错误返回None。
+    pub fn context(&self) -> Option<&RequestContext> {
+        match self {
+            WecomError::Api { context, .. }
+            | WecomError::Http { context, .. }
+            | WecomError::Json { context, .. }
+            | WecomError::IpNotAllowed { context, .. }
+            | WecomError::AllRecipientsInvalid { context, .. }
+            | WecomError::UnlicensedRecipients { context, .. }
+            | WecomError::Timeout { context, .. }
+            | WecomError::MissingField { context, .. } => context.as_deref(),
+            _ => None,
+        }
+    }
+
+    // 补充请求上下文。已有上下文时保留原值，以便保留最内层的出错请求。
+    pub(crate) fn with_context(mut self, ctx: RequestContext) -> Self {
+        if let WecomError::Api { context, .. }
+        | WecomError::Http { context, .. }
+        | WecomError::Json { context, .. }
+        | WecomError::IpNotAllowed { context, .. }
+        | WecomError::AllRecipientsInvalid { context, .. }
+        | WecomError::UnlicensedRecipients { context, .. }
+        | WecomError::Timeout { context, .. }
+        | WecomError::MissingField { context, .. } = &mut self
+        {
+            context.get_or_insert_with(|| Box::new(ctx));
+        }
+        self
+    }
+
+    /// 错误描述。企业微信接口错误返回其errmsg。
+    pub fn message(&self) -> &str {
+        match self {
+            WecomError::Api { msg, .. } => msg,
+            WecomError::Http { .. } => "HTTP请求失败",
+            WecomError::Json { .. } => "JSON处理失败",
+            WecomError::IpNotAllowed { .. } => "不安全的访问IP",
+            WecomError::AllRecipientsInvalid { .. } => "收件人全部非法或无权限",
+            WecomError::Timeout { .. } => "请求超时",
+            WecomError::UnlicensedRecipients { .. } => "收件人未激活接口许可",
+            WecomError::TokenRefreshThrottled { .. } => "Access token更新过于频繁",
+            WecomError::Build(msg) | WecomError::InvalidArgument(msg) => msg,
+            WecomError::ContentTooLong { .. } => "内容超出长度上限",
+            WecomError::Io(_) => "读写本地文件失败",
+            WecomError::InvalidProxyUrl(_) => "代理地址无效",
+            WecomError::InvalidUrl(_) => "链接无效",
+            WecomError::TlsError(_) => "TLS配置无效",
+            WecomError::VaultError(_) => "无法从Vault读取凭据",
+            WecomError::MissingField { .. } => "响应中缺少必需的字段",
+        }
+    }
+}
+
+// 按错误码比较
+impl PartialEq for WecomError {
+    fn eq(&self, other: &Self) -> bool {
+        self.code() == other.code()
+    }
+}
+
+/// `WecomAgent::send_checked()`的错误，按处理方式分为三类
+#[derive(Debug)]
+pub enum SendError {
+    /// 传输错误：网络不通、HTTP请求失败或响应无法解析。通常可以重试。
+    Transport(WecomError),
+    /// 企业微信返回了非0的errcode，如收件人全部无效（81013）。重试通常无效。
+    Api(WecomError),
+    /// 频率受限：access token更新过于频繁或接口调用触发了频率限制。需要等待后再试。
+    Throttled(WecomError),
+}
+
+impl SendError {
+    /// 错误详情
+    pub fn inner(&self) -> &WecomError {
+        match self {
+            SendError::Transport(e) | SendError::Api(e) | SendError::Throttled(e) => e,
+        }
+    }
+
+    pub fn into_inner(self) -> WecomError {
+        match self {
+            SendError::Transport(e) | SendError::Api(e) | SendError::Throttled(e) => e,
+        }
+    }
+}
+
+impl From<WecomError> for SendError {
+    fn from(e: WecomError) -> Self {
+        match e {
+            WecomError::Http { .. } | WecomError::Json { .. } | WecomError::Timeout { .. } => {
+                SendError::Transport(e)
+            }
+            WecomError::TokenRefreshThrottled { .. } => SendError::Throttled(e),
+            _ if e.is_rate_limited() => SendError::Throttled(e),
+            _ => SendError::Api(e),
+        }
+    }
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner().fmt(f)
+    }
+}
+
+impl StdError for SendError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.inner().source()
+    }
+}
+
+/// 上传素材并发送的便捷方法（如`WecomAgent::send_file()`）的错误，按失败的阶段区分
+#[derive(Debug)]
+pub enum MediaSendError {
+    /// 上传素材失败，消息未发送
+    Upload(WecomError),
+    /// 素材已上传，发送消息失败
+    Send(WecomError),
+}
+
+impl MediaSendError {
+    /// 错误详情
+    pub fn inner(&self) -> &WecomError {
+        match self {
+            MediaSendError::Upload(e) | MediaSendError::Send(e) => e,
+        }
+    }
+
+    pub fn into_inner(self) -> WecomError {
+        match self {
+            MediaSendError::Upload(e) | MediaSendError::Send(e) => e,
+        }
+    }
+}
+
+impl fmt::Display for MediaSendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaSendError::Upload(e) => write!(f, "上传素材失败: {e}"),
+            MediaSendError::Send(e) => write!(f, "发送消息失败: {e}"),
+        }
+    }
+}
+
+impl StdError for MediaSendError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.inner().source()
+    }
+}
+
+// 60020的errmsg形如"not allow to access from your ip, hint: [...], from ip: 1.2.3.4, more info at ..."
+fn parse_ip(msg: &str) -> Option<String> {
+    msg.split(|c: char| c == ',' || c == '[' || c == ']' || c.is_whitespace())
+        .find(|token| token.parse::<IpAddr>().is_ok())
+        .map(String::from)
+}
+
+impl fmt::Display for WecomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WecomError::Api { code, msg, .. } => write!(f, "Error code: {code}, {msg}")?,
+            WecomError::Http { .. } => write!(f, "HTTP请求失败")?,
+            WecomError::Json { .. } => write!(f, "JSON处理失败")?,
+            WecomError::IpNotAllowed { your_ip, .. } => match your_ip {
+                Some(ip) => write!(f, "访问IP不在可信IP列表中，请把{ip}加入企业微信后台可信IP")?,
+                None => write!(
+                    f,
+                    "访问IP不在可信IP列表中，请把出口IP加入企业微信后台可信IP"
+                )?,
+            },
+            WecomError::AllRecipientsInvalid {
+                users,
+                parties,
+                tags,
+                ..
+            } => write!(
+                f,
+                "收件人全部非法或无权限：成员{}个、部门{}个、标签{}个",
+                users.len(),
+                parties.len(),
+                tags.len()
+            )?,
+            WecomError::UnlicensedRecipients { users, .. } => {
+                write!(f, "{}名收件人未激活接口许可，消息未送达", users.len())?
+            }
+            WecomError::Timeout { timeout, .. } => {
+                write!(f, "请求超时，时限{}毫秒", timeout.as_millis())?
+            }
+            WecomError::TokenRefreshThrottled { since, .. } => write!(
+                f,
+                "Access token更新过于频繁。上次更新于{}秒前。",
+                since.as_secs()
+            )?,
+            WecomError::Build(msg) => write!(f, "消息构造失败: {msg}")?,
+            WecomError::InvalidArgument(msg) => write!(f, "参数不合法: {msg}")?,
+            WecomError::Io(_) => write!(f, "读写本地文件失败")?,
+            WecomError::ContentTooLong {
+                field,
+                actual,
+                limit,
+                unit,
+            } => write!(
+                f,
+                "{field}超出长度上限：{actual}个{unit}，上限{limit}个{unit}"
+            )?,
+            WecomError::InvalidProxyUrl(addr) => write!(f, "代理地址无效: {addr}")?,
+            WecomError::InvalidUrl(url) => write!(f, "链接无效，须为HTTPS地址: {url}")?,
+            WecomError::TlsError(msg) => write!(f, "TLS配置无效: {msg}")?,
+            WecomError::VaultError(msg) => write!(f, "无法从Vault读取凭据: {msg}")?,
+            WecomError::MissingField { field, .. } => write!(f, "响应中缺少{field}")?,
+        }
+        if let Some(context) = self.context() {
+            write!(f, " ({context})")?;
+        }
+        Ok(())
+    }
+}
+
+// 底层错误通过source()暴露，Display中不再重复其内容。
+impl StdError for WecomError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            WecomError::Http { source, .. } => Some(source),
+            WecomError::Json { source, .. } => Some(source),
+            WecomError::Io(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// reqwest的错误信息中包含请求URL，其中可能带有access token，因此需要去除。
+impl From<reqwest::Error> for WecomError {
+    fn from(e: reqwest::Error) -> Self {
+        WecomError::Http {
+            source: e.without_url(),
+            context: None,
+        }
+    }
+}
+
+impl From<std::io::Error> for WecomError {
+    fn from(e: std::io::Error) -> Self {
+        WecomError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for WecomError {
+    fn from(e: serde_json::Error) -> Self {
+        WecomError::Json {
+            source: e,
+            context: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_code_and_message() {
+        let e = WecomError::Api {
+            code: 40001,
+            msg: "invalid credential".to_string(),
+            context: None,
+        };
+        assert_eq!(e.code(), 40001);
+        assert_eq!(e.message(), "invalid credential");
+
+        let e = WecomError::TokenRefreshThrottled {
+            since: Duration::from_secs(3),
+            backoff: Duration::from_secs(10),
+        };
+        assert_eq!(e.code(), WecomError::CODE_TOKEN_REFRESH_THROTTLED);
+
+        let e = WecomError::Build("收件人不可为空".to_string());
+        assert_eq!(e.code(), WecomError::CODE_BUILD);
+        assert_eq!(e.message(), "收件人不可为空");
+
+        let e: WecomError = serde_json::from_str::<i64>("x").unwrap_err().into();
+        assert_eq!(e.code(), WecomError::CODE_JSON);
+    }
+
+    #[test]
+    fn test_eq_by_code() {
+        let a = WecomError::Api {
+            code: 81013,
+            msg: "user & party & tag all invalid".to_string(),
+            context: None,
+        };
+        let b = WecomError::Api {
+            code: 81013,
+            msg: String::new(),
+            context: None,
+        };
+        assert_eq!(a, b);
+        assert_ne!(a, WecomError::Build(String::new()));
+    }
+
+    #[test]
+    fn test_semantic_checks() {
+        let api = |code| WecomError::Api {
+            code,
+            msg: String::new(),
+            context: None,
+        };
+        assert!(api(40014).is_token_invalid());
+        assert!(api(42001).is_token_invalid());
+        assert!(api(45009).is_rate_limited());
+        assert!(api(45011).is_rate_limited());
+        assert!(api(81013).is_no_valid_recipient());
+        assert!(api(60020).is_ip_not_allowed());
+        assert!(api(60011).is_permission_denied());
+        assert!(!api(40001).is_token_invalid());
+        assert!(!WecomError::Build(String::new()).is_rate_limited());
+    }
+
+    #[test]
+    fn test_context_display() {
+        let msg = serde_json::json!({
+            "touser": "robin|tom",
+            "toparty": "",
+            "totag": "x",
+            "msgtype": "text",
+            "agentid": 42,
+        });
+        let e = WecomError::Api {
+            code: 81013,
+            msg: "user & party & tag all invalid".to_string(),
+            context: None,
+        }
+        .with_context(RequestContext::for_message("/cgi-bin/message/send", &msg))
+        .with_context(RequestContext::new("/cgi-bin/gettoken"));
+        let text = e.to_string();
+        assert!(text.contains("path=/cgi-bin/message/send"));
+        assert!(text.contains("msgtype=text"));
+        assert!(text.contains("agentid=42"));
+        assert!(text.contains("recipients=3"));
+        assert!(!text.contains("robin"));
+
+        let e = WecomError::Build(String::new()).with_context(RequestContext::new("/x"));
+        assert!(e.context().is_none());
+    }
+
+    #[test]
+    fn test_source_chain() {
+        let e: WecomError = serde_json::from_str::<i64>("x").unwrap_err().into();
+        let chain = anyhow::Error::from(e);
+        assert!(chain
+            .chain()
+            .any(|c| c.downcast_ref::<serde_json::Error>().is_some()));
+    }
+
+    #[test]
+    fn test_ip_not_allowed() {
+        let samples = [
+            (
+                "not allow to access from your ip, hint: [1585712345_12_a1b2c3], from ip: 113.87.161.22, more info at https://open.work.weixin.qq.com/devtool/query?e=60020",
+                Some("113.87.161.22"),
+            ),
+            (
+                "not allow to access from your ip, hint: [1703055617328790185409498], from ip: 240e:3b4:38e4:1e70::1, more info at https://open.work.weixin.qq.com/devtool/query?e=60020",
+                Some("240e:3b4:38e4:1e70::1"),
+            ),
+            ("not allow to access from your ip", None),
+        ];
+        for (msg, ip) in samples {
+            let e = WecomError::api(60020, msg.to_string());
+            match &e {
+                WecomError::IpNotAllowed { your_ip, .. } => assert_eq!(your_ip.as_deref(), ip),
+                other => panic!("unexpected variant: {other:?}"),
+            }
+            assert_eq!(e.code(), 60020);
+            assert!(e.is_ip_not_allowed());
+            if let Some(ip) = ip {
+                assert!(e
+                    .to_string()
+                    .contains(&format!("请把{ip}加入企业微信后台可信IP")));
+            }
+        }
+    }
+
+    #[test]
+    fn test_send_error_classification() {
+        let json: WecomError = serde_json::from_str::<i64>("x").unwrap_err().into();
+        assert!(matches!(SendError::from(json), SendError::Transport(_)));
+        let throttled = WecomError::TokenRefreshThrottled {
+            since: Duration::from_secs(1),
+            backoff: Duration::from_secs(10),
+        };
+        assert!(matches!(
+            SendError::from(throttled),
+            SendError::Throttled(_)
+        ));
+        let rate_limited = WecomError::api(45009, String::new());
+        assert!(matches!(
+            SendError::from(rate_limited),
+            SendError::Throttled(_)
+        ));
+        let api = WecomError::api(81013, String::new());
+        assert!(matches!(SendError::from(api), SendError::Api(e) if e.code() == 81013));
+        let ip = WecomError::api(60020, String::new());
+        assert!(matches!(SendError::from(ip), SendError::Api(_)));
+    }
+
+    #[tokio::test]
+    async fn test_retry_advice() {
+        // 连接被拒绝的HTTP错误
+        let connect_error: WecomError =
+            reqwest::get("http://127.0.0.1:1").await.unwrap_err().into();
+        let cases = [
+            (connect_error, true, None),
+            (
+                WecomError::TokenRefreshThrottled {
+                    since: Duration::from_secs(3),
+                    backoff: Duration::from_secs(10),
+                },
+                true,
+                Some(Duration::from_secs(7)),
+            ),
+            (
+                WecomError::api(-1, String::new()),
+                true,
+                Some(Duration::from_secs(1)),
+            ),
+            (WecomError::api(40014, String::new()), true, None),
+            (WecomError::api(42001, String::new()), true, None),
+            (
+                WecomError::api(45009, String::new()),
+                true,
+                Some(Duration::from_secs(60)),
+            ),
+            (
+                WecomError::api(45011, String::new()),
+                true,
+                Some(Duration::from_secs(1)),
+            ),
+            (WecomError::api(40001, String::new()), false, None),
+            (WecomError::api(81013, String::new()), false, None),
+            (WecomError::api(60011, String::new()), false, None),
+            (WecomError::api(60020, String::new()), false, None),
+            (WecomError::Build(String::new()), false, None),
+        ];
+        for (e, retryable, retry_after) in cases {
+            assert_eq!(e.retryable(), retryable, "{e}");
+            assert_eq!(e.retry_after(), retry_after, "{e}");
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::WecomAgent;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+impl WecomAgent {
+    /// 检查能否正常访问企业微信API：必要时更新access token，再调用获取API域名IP的接口。
+    #[tracing::instrument(skip(self), fields(corp_id = %self.corp_id))]
+    pub async fn ping(&self) -> Result<(), crate::WecomError> {
+        self.get_api_domain_ips().await.map(|_| ())
+    }
+
+    /// 最近一次健康检查是否成功，可用于负载均衡的健康检查。未启动健康检查时始终为true。
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    /// 启动后台任务，每隔`interval`调用一次`ping()`并更新`is_healthy()`。首次检查立即进行。
+    ///
+    /// 检查失败时记录警告。Agent被释放后，任务在下次唤醒时结束；也可通过返回的`JoinHandle`提前终止。
+    pub fn start_health_check(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let agent = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                let Some(agent) = agent.upgrade() else {
+                    return;
+                };
+                match agent.ping().await {
+                    Ok(()) => {
+                        debug!("Health check passed");
+                        agent.healthy.store(true, Ordering::Relaxed);
+                    }
+                    Err(e) => {
+                        warn!("Health check failed: {e}");
+                        agent.healthy.store(false, Ordering::Relaxed);
+                    }
+                }
+                drop(agent);
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_health_check() {
+        // 连接池中的服务器在释放后仍会继续监听，因此使用独立的服务器
+        let server = MockServer::builder().start().await;
+        Mock::given(method("GET"))
+            .and(path("/cgi-bin/gettoken"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "errcode": 0, "errmsg": "ok", "access_token": "token", "expires_in": 7200
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cgi-bin/get_api_domain_ip"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "errcode": 0, "errmsg": "ok", "ip_list": ["182.254.11.176"]
+            })))
+            .mount(&server)
+            .await;
+        let agent = Arc::new(
+            WecomAgent::builder("corp", "secret")
+                .base_url(&server.uri())
+                .build()
+                .unwrap(),
+        );
+        assert!(agent.is_healthy());
+        agent.ping().await.unwrap();
+
+        // 等待后台任务至少发出一次检查请求（此前已有gettoken与ping两个请求）
+        let handle = agent.start_health_check(Duration::from_millis(20));
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while server.received_requests().await.unwrap().len() < 3 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("health check should run");
+        assert!(agent.is_healthy());
+
+        // 服务不可用后，下一次检查将状态置为false
+        drop(server);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while agent.is_healthy() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("health check should fail once the server is gone");
+        handle.abort();
+    }
+}
@@ -0,0 +1,193 @@
+//! 互联企业消息
+//!
+//! 向互联企业的成员发送应用消息。收件人以数组列出，互联企业的成员写作`CorpId/userid`，
+//! 部门写作`LinkedId/partyid`。
+
+use crate::message::WecomMessage;
+use crate::WecomError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+// 互联企业消息
+// 示例
+// {
+//     "touser" : ["userid1", "CorpId1/userid2"],
+//     "toparty" : ["partyid1", "LinkedId1/partyid2"],
+//     "totag" : ["tagid1"],
+//     "toall" : 0,
+//     "msgtype" : "text",
+//     "agentid" : 1,
+//     "text" : {
+//         "content" : "你的快递已到"
+//     },
+//     "safe" : 0
+// }
+/// 互联企业消息，由`LinkedCorpMessageBuilder`构造
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct LinkedCorpMessage(Value);
+
+impl LinkedCorpMessage {
+    pub fn msg_type(&self) -> Option<&str> {
+        self.0["msgtype"].as_str()
+    }
+}
+
+/// 互联企业消息的构造器
+#[derive(Debug, Default)]
+pub struct LinkedCorpMessageBuilder {
+    users: Vec<String>,
+    parties: Vec<String>,
+    tags: Vec<String>,
+    to_all: bool,
+    agent_id: Option<usize>,
+    safe: bool,
+}
+
+impl LinkedCorpMessageBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 成员，互联企业的成员写作`CorpId/userid`
+    pub fn to_users(mut self, users: Vec<&str>) -> Self {
+        self.users = users.into_iter().map(str::to_owned).collect();
+        self
+    }
+
+    /// 部门，互联企业的部门写作`LinkedId/partyid`
+    pub fn to_parties(mut self, parties: Vec<&str>) -> Self {
+        self.parties = parties.into_iter().map(str::to_owned).collect();
+        self
+    }
+
+    pub fn to_tags(mut self, tags: Vec<&str>) -> Self {
+        self.tags = tags.into_iter().map(str::to_owned).collect();
+        self
+    }
+
+    /// 发送给应用可见范围内的全部成员
+    pub fn to_all(mut self) -> Self {
+        self.to_all = true;
+        self
+    }
+
+    pub fn from_agent(mut self, agent_id: usize) -> Self {
+        self.agent_id = Some(agent_id);
+        self
+    }
+
+    /// 是否为保密消息
+    pub fn with_safe(mut self, safe: bool) -> Self {
+        self.safe = safe;
+        self
+    }
+
+    pub fn build<T>(&self, content: T) -> Result<LinkedCorpMessage, WecomError>
+    where
+        T: Serialize + WecomMessage,
+    {
+        if !self.to_all
+            && [&self.users, &self.parties, &self.tags]
+                .iter()
+                .all(|x| x.is_empty())
+        {
+            return Err(WecomError::Build("收件人不可为空".to_string()));
+        }
+        let Some(agent_id) = self.agent_id else {
+            return Err(WecomError::Build("AgentID不可为空".to_string()));
+        };
+        content.validate()?;
+
+        let mut msg = json!({
+            "touser": self.users,
+            "toparty": self.parties,
+            "totag": self.tags,
+            "toall": i64::from(self.to_all),
+            "msgtype": content.msg_type().as_str(),
+            "agentid": agent_id,
+            "safe": i64::from(self.safe),
+        });
+        msg[content.key()] = serde_json::to_value(content.value())?;
+        Ok(LinkedCorpMessage(msg))
+    }
+}
+
+// 发送互联企业消息的返回结果
+// {
+//     "errcode" : 0,
+//     "errmsg" : "ok",
+//     "invaliduser" : ["userid1", "CorpId1/userid2"],
+//     "invalidparty" : ["partyid1", "LinkedId1/partyid2"],
+//     "invalidtag" : ["tagid1"]
+// }
+/// 互联企业消息的发送结果。与应用消息不同，无效的收件人以数组返回。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LinkedCorpSendResponse {
+    #[serde(default)]
+    invaliduser: Vec<String>,
+    #[serde(default)]
+    invalidparty: Vec<String>,
+    #[serde(default)]
+    invalidtag: Vec<String>,
+}
+
+impl LinkedCorpSendResponse {
+    /// 无效或无权限的成员
+    pub fn invalid_users(&self) -> &[String] {
+        &self.invaliduser
+    }
+
+    /// 无效或无权限的部门
+    pub fn invalid_parties(&self) -> &[String] {
+        &self.invalidparty
+    }
+
+    /// 无效或无权限的标签
+    pub fn invalid_tags(&self) -> &[String] {
+        &self.invalidtag
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::message::Text;
+
+    #[test]
+    fn test_builder() {
+        let msg = LinkedCorpMessageBuilder::default()
+            .to_users(vec!["robin", "wwcorp/tom"])
+            .to_parties(vec!["link/2"])
+            .from_agent(1)
+            .build(Text::new("hello".to_string()))
+            .unwrap();
+        assert_eq!(msg.msg_type(), Some("text"));
+        assert_eq!(
+            serde_json::to_value(&msg).unwrap(),
+            json!({
+                "touser": ["robin", "wwcorp/tom"],
+                "toparty": ["link/2"],
+                "totag": [],
+                "toall": 0,
+                "msgtype": "text",
+                "agentid": 1,
+                "text": {"content": "hello"},
+                "safe": 0
+            })
+        );
+
+        let msg = LinkedCorpMessageBuilder::default()
+            .to_all()
+            .from_agent(1)
+            .build(Text::new("hello".to_string()))
+            .unwrap();
+        assert_eq!(serde_json::to_value(&msg).unwrap()["toall"], 1);
+
+        let builder = LinkedCorpMessageBuilder::default().from_agent(1);
+        assert!(matches!(
+            builder.build(Text::new("hello".to_string())),
+            Err(WecomError::Build(_))
+        ));
+    }
+}
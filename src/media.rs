@@ -0,0 +1,844 @@
+use crate::{LengthUnit, WecomError};
+use md5::{Digest, Md5};
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// 临时素材的有效期：3天
+pub const TEMPORARY_MEDIA_TTL: Duration = Duration::from_secs(3 * 24 * 60 * 60);
+
+/// 上传进度回调，参数依次为已上传字节数与总字节数
+pub type ProgressCallback = Arc<dyn Fn(u64, u64) + Send + Sync>;
+
+/// 临时素材类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaType {
+    Image,
+    Voice,
+    Video,
+    File,
+}
+
+impl MediaType {
+    /// 上传接口中`type`参数的取值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaType::Image => "image",
+            MediaType::Voice => "voice",
+            MediaType::Video => "video",
+            MediaType::File => "file",
+        }
+    }
+
+    // `as_str()`的逆操作
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "image" => Some(MediaType::Image),
+            "voice" => Some(MediaType::Voice),
+            "video" => Some(MediaType::Video),
+            "file" => Some(MediaType::File),
+            _ => None,
+        }
+    }
+
+    /// 该类型素材的字节数上限
+    pub fn max_size(&self) -> u64 {
+        const MB: u64 = 1024 * 1024;
+        match self {
+            MediaType::Image => 10 * MB,
+            MediaType::Voice => 2 * MB,
+            MediaType::Video => 10 * MB,
+            MediaType::File => 20 * MB,
+        }
+    }
+
+    /// 上传前在本地检查素材是否符合企业微信的要求，以免等到服务端返回40009等错误。
+    ///
+    /// - 所有素材不小于5个字节，且不超过[`max_size()`](Self::max_size)；
+    /// - 图片须为JPG或PNG格式，语音须为AMR格式且不超过60秒，视频须为MP4格式。
+    ///
+    /// 格式依据文件头识别，同时要求文件扩展名与格式相符。普通文件不检查格式。
+    pub fn validate(&self, filename: &str, data: &[u8]) -> Result<(), WecomError> {
+        if data.len() < MIN_MEDIA_BYTES {
+            return Err(WecomError::InvalidArgument(format!(
+                "素材不能小于{MIN_MEDIA_BYTES}个字节"
+            )));
+        }
+        self.check_size(data.len() as u64)?;
+
+        let (extensions, matches): (&[&str], bool) = match self {
+            MediaType::Image => (&["jpg", "jpeg", "png"], is_jpg(data) || is_png(data)),
+            MediaType::Voice => (&["amr"], data.starts_with(AMR_MAGIC)),
+            MediaType::Video => (&["mp4"], data.get(4..8) == Some(b"ftyp")),
+            MediaType::File => return Ok(()),
+        };
+        let extension = std::path::Path::new(filename)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        if !matches || !extension.is_some_and(|e| extensions.contains(&e.as_str())) {
+            return Err(WecomError::InvalidArgument(format!(
+                "{}素材仅支持{}格式: {filename}",
+                self.as_str(),
+                extensions.join("/")
+            )));
+        }
+
+        if *self == MediaType::Voice {
+            let duration = amr_duration(data);
+            if duration > MAX_VOICE_DURATION {
+                return Err(WecomError::InvalidArgument(format!(
+                    "语音时长{}秒，超出上限{}秒",
+                    duration.as_secs_f32(),
+                    MAX_VOICE_DURATION.as_secs()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // 超出上限时返回ContentTooLong，单位为字节
+    pub(crate) fn check_size(&self, size: u64) -> Result<(), WecomError> {
+        if size > self.max_size() {
+            return Err(WecomError::ContentTooLong {
+                field: "media",
+                actual: size as usize,
+                limit: self.max_size() as usize,
+                unit: LengthUnit::Bytes,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// 临时素材的最小字节数
+pub const MIN_MEDIA_BYTES: usize = 5;
+
+/// 语音素材的最长时长
+pub const MAX_VOICE_DURATION: Duration = Duration::from_secs(60);
+
+const AMR_MAGIC: &[u8] = b"#!AMR\n";
+
+pub(crate) fn is_jpg(data: &[u8]) -> bool {
+    data.starts_with(&[0xFF, 0xD8, 0xFF])
+}
+
+pub(crate) fn is_png(data: &[u8]) -> bool {
+    data.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A])
+}
+
+// AMR-NB语音的时长。每帧20毫秒，帧长由帧头中的编码模式决定。
+fn amr_duration(data: &[u8]) -> Duration {
+    const FRAME_SIZES: [usize; 16] = [12, 13, 15, 17, 19, 20, 26, 31, 5, 0, 0, 0, 0, 0, 0, 0];
+    let mut frames = 0u32;
+    let mut i = AMR_MAGIC.len();
+    while i < data.len() {
+        i += 1 + FRAME_SIZES[((data[i] >> 3) & 0x0F) as usize];
+        frames += 1;
+    }
+    Duration::from_millis(20) * frames
+}
+
+// 上传临时素材的返回结果
+// 示例
+// {
+//    "errcode": 0,
+//    "errmsg": "",
+//    "type": "image",
+//    "media_id": "1G6nrLmr5EC3MMb_-zK1dDdzmd0p7cNliYu9V5w7o8K0",
+//    "created_at": "1380000000"
+// }
+#[derive(Debug, Deserialize)]
+pub struct UploadMediaResponse {
+    errcode: i64,
+    errmsg: String,
+    #[serde(rename = "type")]
+    media_type: Option<String>,
+    media_id: Option<String>,
+    created_at: Option<String>,
+}
+
+impl UploadMediaResponse {
+    pub fn is_error(&self) -> bool {
+        self.errcode != 0
+    }
+
+    pub fn error_code(&self) -> i64 {
+        self.errcode
+    }
+
+    pub fn error_msg(&self) -> &str {
+        &self.errmsg
+    }
+
+    pub fn media_type(&self) -> Option<&str> {
+        self.media_type.as_deref()
+    }
+
+    pub fn media_id(&self) -> Option<&str> {
+        self.media_id.as_deref()
+    }
+
+    pub fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+
+    // 上传时刻。响应中缺失或无法解析时返回None。
+    pub(crate) fn uploaded_at(&self) -> Option<SystemTime> {
+        self.created_at
+            .as_deref()
+            .and_then(|t| t.parse::<u64>().ok())
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+    }
+}
+
+// 上传图片的返回结果
+// 示例
+// {
+//    "errcode": 0,
+//    "errmsg": "",
+//    "url" : "http://p.qpic.cn/pic_wework/3474110808/7a7c8471673ff0f178f63447935d35a5c1247a7f31d9c060/0"
+// }
+#[derive(Debug, Deserialize)]
+pub(crate) struct UploadImageResponse {
+    pub errcode: i64,
+    pub errmsg: String,
+    pub url: Option<String>,
+}
+
+/// 通过uploadimg上传的图片的字节数上限
+pub const MAX_IMAGE_BYTES: usize = 1024 * 1024;
+
+// 检查uploadimg上传的图片：按文件头识别JPG或PNG格式，且不超过1MB
+pub(crate) fn check_image(data: &[u8]) -> Result<(), WecomError> {
+    if !is_jpg(data) && !is_png(data) {
+        return Err(WecomError::InvalidArgument(
+            "图片仅支持JPG与PNG格式".to_string(),
+        ));
+    }
+    if data.len() > MAX_IMAGE_BYTES {
+        return Err(WecomError::ContentTooLong {
+            field: "image",
+            actual: data.len(),
+            limit: MAX_IMAGE_BYTES,
+            unit: LengthUnit::Bytes,
+        });
+    }
+    Ok(())
+}
+
+/// 下载的临时素材
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadedMedia {
+    data: Vec<u8>,
+    content_type: Option<String>,
+    filename: Option<String>,
+}
+
+impl DownloadedMedia {
+    pub fn new(data: Vec<u8>, content_type: Option<String>, filename: Option<String>) -> Self {
+        Self {
+            data,
+            content_type,
+            filename,
+        }
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// 响应头中的Content-Type
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// 从响应头Content-Disposition中解析出的文件名
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
+}
+
+/// 高清语音素材，格式为speex
+#[derive(Debug, Clone, PartialEq)]
+pub struct HdVoice {
+    media: DownloadedMedia,
+}
+
+impl HdVoice {
+    pub(crate) fn new(media: DownloadedMedia) -> Self {
+        Self { media }
+    }
+
+    /// 音频格式，固定为`speex`
+    pub fn format(&self) -> &'static str {
+        "speex"
+    }
+
+    pub fn media(&self) -> &DownloadedMedia {
+        &self.media
+    }
+
+    pub fn into_media(self) -> DownloadedMedia {
+        self.media
+    }
+}
+
+// 从Content-Disposition中解析文件名，优先采用RFC 5987编码的`filename*`
+pub(crate) fn content_disposition_filename(value: &str) -> Option<String> {
+    let mut filename = None;
+    for param in value.split(';') {
+        let Some((key, v)) = param.split_once('=') else {
+            continue;
+        };
+        match key.trim().to_ascii_lowercase().as_str() {
+            // 形如UTF-8''%E6%96%87%E4%BB%B6.txt
+            "filename*" => {
+                if let Some(encoded) = v.trim().rsplit('\'').next() {
+                    return Some(percent_decode(encoded));
+                }
+            }
+            "filename" => filename = Some(v.trim().trim_matches('"').to_string()),
+            _ => {}
+        }
+    }
+    filename.filter(|f| !f.is_empty())
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                decoded.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// 已上传的临时素材。企业微信的临时素材在上传3天后失效。
+///
+/// 可直接由上传接口的响应反序列化，`created_at`为字符串或数字形式的Unix时间戳（秒）均可。
+#[doc(alias = "UploadedMedia")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemporaryMedia {
+    media_id: String,
+    uploaded_at: SystemTime,
+    media_type: MediaType,
+}
+
+impl TemporaryMedia {
+    pub fn new(media_id: String, uploaded_at: SystemTime, media_type: MediaType) -> Self {
+        Self {
+            media_id,
+            uploaded_at,
+            media_type,
+        }
+    }
+
+    pub fn media_id(&self) -> &str {
+        &self.media_id
+    }
+
+    pub fn uploaded_at(&self) -> SystemTime {
+        self.uploaded_at
+    }
+
+    pub fn media_type(&self) -> MediaType {
+        self.media_type
+    }
+
+    /// 素材失效时刻
+    pub fn expires_at(&self) -> SystemTime {
+        self.uploaded_at + TEMPORARY_MEDIA_TTL
+    }
+
+    /// 素材是否已失效
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(SystemTime::now())
+    }
+
+    /// 素材的剩余有效时长。已失效时返回None。
+    pub fn remaining_validity(&self) -> Option<Duration> {
+        self.remaining_validity_at(SystemTime::now())
+    }
+
+    fn is_expired_at(&self, now: SystemTime) -> bool {
+        now >= self.expires_at()
+    }
+
+    fn remaining_validity_at(&self, now: SystemTime) -> Option<Duration> {
+        match self.expires_at().duration_since(now) {
+            Ok(d) if !d.is_zero() => Some(d),
+            _ => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TemporaryMedia {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Timestamp {
+            Str(String),
+            Int(u64),
+        }
+
+        #[derive(Deserialize)]
+        struct Raw {
+            #[serde(rename = "type")]
+            media_type: String,
+            media_id: String,
+            created_at: Timestamp,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        let media_type = MediaType::from_str(&raw.media_type)
+            .ok_or_else(|| de::Error::custom(format!("unknown media type: {}", raw.media_type)))?;
+        let secs = match raw.created_at {
+            Timestamp::Int(secs) => secs,
+            Timestamp::Str(s) => s
+                .trim()
+                .parse()
+                .map_err(|_| de::Error::custom(format!("invalid created_at: {s}")))?,
+        };
+        Ok(Self::new(
+            raw.media_id,
+            UNIX_EPOCH + Duration::from_secs(secs),
+            media_type,
+        ))
+    }
+}
+
+// 记录已上传的临时素材，以media_id为键。写入时清理已失效的记录。
+// `hashes`记录素材内容的md5与media_id的对应关系，用于复用内容相同的素材。
+#[derive(Debug, Default)]
+pub(crate) struct MediaCache {
+    entries: HashMap<String, TemporaryMedia>,
+    hashes: HashMap<String, String>,
+}
+
+impl MediaCache {
+    pub fn list(&self) -> Vec<TemporaryMedia> {
+        self.entries.values().cloned().collect()
+    }
+
+    // 记录素材，`now`为当前时刻
+    pub fn insert(&mut self, media: TemporaryMedia, now: SystemTime) {
+        self.entries.retain(|_, m| !m.is_expired_at(now));
+        self.entries.insert(media.media_id.clone(), media);
+        self.hashes.retain(|_, id| self.entries.contains_key(id));
+    }
+
+    // 记录素材并关联其内容的md5
+    pub fn insert_with_hash(&mut self, media: TemporaryMedia, hash: String, now: SystemTime) {
+        let media_id = media.media_id.clone();
+        self.insert(media, now);
+        self.hashes.insert(hash, media_id);
+    }
+
+    // 查找内容相同且尚未失效的素材
+    pub fn find_by_hash(&self, hash: &str, now: SystemTime) -> Option<TemporaryMedia> {
+        self.hashes
+            .get(hash)
+            .and_then(|id| self.entries.get(id))
+            .filter(|m| !m.is_expired_at(now))
+            .cloned()
+    }
+}
+
+// 素材内容的md5，小写十六进制
+pub(crate) fn content_hash(data: &[u8]) -> String {
+    format!("{:x}", Md5::digest(data))
+}
+
+// 按企业微信要求构造素材上传的multipart/form-data请求体，返回Content-Type与请求体。
+// Content-Disposition中须带有filelength，reqwest的multipart不支持该参数，因此手动构造。
+pub(crate) fn multipart_body(filename: &str, data: &[u8]) -> (String, Vec<u8>) {
+    let boundary = boundary_for(data);
+    let mut body = multipart_head(&boundary, filename, data.len() as u64);
+    body.extend_from_slice(data);
+    body.extend_from_slice(&multipart_tail(&boundary));
+    (format!("multipart/form-data; boundary={boundary}"), body)
+}
+
+// 文件内容之前的部分
+pub(crate) fn multipart_head(boundary: &str, filename: &str, length: u64) -> Vec<u8> {
+    let filename = filename.replace('\\', "\\\\").replace('"', "\\\"");
+    format!(
+        "--{boundary}\r\n\
+         Content-Disposition: form-data; name=\"media\"; filename=\"{filename}\"; filelength={length}\r\n\
+         Content-Type: application/octet-stream\r\n\r\n"
+    )
+    .into_bytes()
+}
+
+// 文件内容之后的部分
+pub(crate) fn multipart_tail(boundary: &str) -> Vec<u8> {
+    format!("\r\n--{boundary}--\r\n").into_bytes()
+}
+
+// 生成不出现在数据中的分隔符
+pub(crate) fn boundary_for(data: &[u8]) -> String {
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    (0u32..)
+        .map(|n| format!("wecom-agent-{seed:x}-{n}"))
+        .find(|b| !data.windows(b.len()).any(|w| w == b.as_bytes()))
+        .expect("Boundary should be found")
+}
+
+// 读取数据时统计已读字节数，并通过回调通知上传进度。回调中的panic被捕获，不影响上传。
+pub(crate) struct ProgressReader<R> {
+    inner: R,
+    uploaded: u64,
+    total: u64,
+    on_progress: ProgressCallback,
+}
+
+impl<R> ProgressReader<R> {
+    pub fn new(inner: R, total: u64, on_progress: ProgressCallback) -> Self {
+        Self {
+            inner,
+            uploaded: 0,
+            total,
+            on_progress,
+        }
+    }
+}
+
+impl<R> AsyncRead for ProgressReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let filled_before = buf.filled().len();
+        let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = poll {
+            let n = (buf.filled().len() - filled_before) as u64;
+            if n > 0 {
+                self.uploaded += n;
+                let (uploaded, total) = (self.uploaded, self.total);
+                let on_progress = &self.on_progress;
+                let _ = std::panic::catch_unwind(AssertUnwindSafe(|| on_progress(uploaded, total)));
+            }
+        }
+        poll
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn test_progress_reader() {
+        let data = vec![7u8; 10_000];
+        let total = data.len() as u64;
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let sink = records.clone();
+        let mut reader = ProgressReader::new(
+            std::io::Cursor::new(data),
+            total,
+            Arc::new(move |uploaded, total| sink.lock().unwrap().push((uploaded, total))),
+        );
+
+        let mut chunk = [0u8; 1024];
+        while reader.read(&mut chunk).await.unwrap() > 0 {}
+
+        let records = records.lock().unwrap();
+        assert!(records.len() > 1);
+        assert!(records.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(records.iter().all(|&(_, t)| t == total));
+        assert_eq!(records.last().unwrap().0, total);
+    }
+
+    #[tokio::test]
+    async fn test_progress_reader_survives_panic() {
+        let mut reader = ProgressReader::new(
+            std::io::Cursor::new(vec![7u8; 100]),
+            100,
+            Arc::new(|_, _| panic!("callback failed")),
+        );
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await.unwrap();
+        assert_eq!(data.len(), 100);
+    }
+
+    #[test]
+    fn test_temporary_media_expiry() {
+        let uploaded_at = UNIX_EPOCH + Duration::from_secs(1_380_000_000);
+        let media = TemporaryMedia::new("MEDIA_ID".to_string(), uploaded_at, MediaType::Image);
+        assert_eq!(media.expires_at(), uploaded_at + TEMPORARY_MEDIA_TTL);
+
+        let just_before = media.expires_at() - Duration::from_secs(1);
+        assert!(!media.is_expired_at(just_before));
+        assert_eq!(
+            media.remaining_validity_at(just_before),
+            Some(Duration::from_secs(1))
+        );
+
+        assert!(media.is_expired_at(media.expires_at()));
+        assert_eq!(media.remaining_validity_at(media.expires_at()), None);
+        assert_eq!(
+            media.remaining_validity_at(uploaded_at),
+            Some(TEMPORARY_MEDIA_TTL)
+        );
+    }
+
+    #[test]
+    fn test_deserialize_temporary_media() {
+        let media: TemporaryMedia = serde_json::from_str(
+            r#"{
+                "errcode": 0,
+                "errmsg": "",
+                "type": "image",
+                "media_id": "1G6nrLmr5EC3MMb_-zK1dDdzmd0p7cNliYu9V5w7o8K0",
+                "created_at": "1380000000"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            media,
+            TemporaryMedia::new(
+                "1G6nrLmr5EC3MMb_-zK1dDdzmd0p7cNliYu9V5w7o8K0".to_string(),
+                UNIX_EPOCH + Duration::from_secs(1_380_000_000),
+                MediaType::Image
+            )
+        );
+        assert_eq!(
+            media.expires_at(),
+            UNIX_EPOCH + Duration::from_secs(1_380_000_000 + 3 * 24 * 60 * 60)
+        );
+        assert!(media.is_expired());
+
+        // 数字形式的created_at
+        let media: TemporaryMedia = serde_json::from_value(serde_json::json!({
+            "type": "file", "media_id": "M", "created_at": 1380000000
+        }))
+        .unwrap();
+        assert_eq!(media.media_type(), MediaType::File);
+
+        for value in [
+            serde_json::json!({"type": "file", "media_id": "M", "created_at": "yesterday"}),
+            serde_json::json!({"type": "gif", "media_id": "M", "created_at": "1380000000"}),
+            serde_json::json!({"errcode": 40004, "errmsg": "invalid media type"}),
+        ] {
+            assert!(serde_json::from_value::<TemporaryMedia>(value).is_err());
+        }
+    }
+
+    #[test]
+    fn test_multipart_body() {
+        let (content_type, body) = multipart_body("a\"b.txt", b"data");
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.starts_with(&format!("--{boundary}\r\n")));
+        assert!(body.contains("filename=\"a\\\"b.txt\"; filelength=4\r\n"));
+        assert!(body.ends_with(&format!("\r\ndata\r\n--{boundary}--\r\n")));
+    }
+
+    // 由`frames`个12.2kbps帧（每帧32字节）组成的AMR语音
+    fn amr(frames: usize) -> Vec<u8> {
+        let mut data = AMR_MAGIC.to_vec();
+        for _ in 0..frames {
+            data.push(7 << 3 | 0x04);
+            data.extend_from_slice(&[0; 31]);
+        }
+        data
+    }
+
+    fn mp4(len: usize) -> Vec<u8> {
+        let mut data = vec![0, 0, 0, 0x20];
+        data.extend_from_slice(b"ftypisom");
+        data.resize(len, 0);
+        data
+    }
+
+    fn jpg(len: usize) -> Vec<u8> {
+        let mut data = vec![0xFF, 0xD8, 0xFF, 0xE0];
+        data.resize(len, 0);
+        data
+    }
+
+    #[test]
+    fn test_validate_media() {
+        const MB: usize = 1024 * 1024;
+        let invalid = |r: Result<(), WecomError>| matches!(r, Err(WecomError::InvalidArgument(_)));
+        let too_long = |r: Result<(), WecomError>| {
+            matches!(r, Err(WecomError::ContentTooLong { field: "media", .. }))
+        };
+
+        // 所有类型均不小于5个字节
+        for media_type in [
+            MediaType::Image,
+            MediaType::Voice,
+            MediaType::Video,
+            MediaType::File,
+        ] {
+            assert!(invalid(media_type.validate("a.txt", b"1234")));
+        }
+        assert!(MediaType::File.validate("a.txt", b"12345").is_ok());
+        assert!(too_long(
+            MediaType::File.validate("a.bin", &vec![0; 20 * MB + 1])
+        ));
+        assert!(MediaType::File.validate("a.bin", &vec![0; 20 * MB]).is_ok());
+
+        // 图片
+        assert!(MediaType::Image.validate("a.JPG", &jpg(10 * MB)).is_ok());
+        assert!(too_long(
+            MediaType::Image.validate("a.jpg", &jpg(10 * MB + 1))
+        ));
+        assert!(invalid(MediaType::Image.validate("a.gif", &jpg(16))));
+        assert!(invalid(MediaType::Image.validate("a.png", b"GIF89a..")));
+        assert!(invalid(MediaType::Image.validate("png", &jpg(16))));
+
+        // 语音
+        assert!(MediaType::Voice.validate("a.amr", &amr(3000)).is_ok());
+        assert!(invalid(MediaType::Voice.validate("a.amr", &amr(3001))));
+        assert!(invalid(MediaType::Voice.validate("a.mp3", &amr(10))));
+        assert!(invalid(MediaType::Voice.validate("a.amr", &mp4(64))));
+        let mut large = amr(1);
+        large.resize(2 * MB + 1, 0);
+        assert!(too_long(MediaType::Voice.validate("a.amr", &large)));
+
+        // 视频
+        assert!(MediaType::Video.validate("a.mp4", &mp4(10 * MB)).is_ok());
+        assert!(too_long(
+            MediaType::Video.validate("a.mp4", &mp4(10 * MB + 1))
+        ));
+        assert!(invalid(MediaType::Video.validate("a.mov", &mp4(64))));
+        assert!(invalid(MediaType::Video.validate("a.mp4", &jpg(64))));
+    }
+
+    #[test]
+    fn test_amr_duration() {
+        assert_eq!(amr_duration(&amr(0)), Duration::ZERO);
+        assert_eq!(amr_duration(&amr(50)), Duration::from_secs(1));
+        // 静音帧（NO_DATA）只占1个字节
+        let mut data = amr(1);
+        data.extend_from_slice(&[15 << 3; 4]);
+        assert_eq!(amr_duration(&data), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_check_image() {
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0];
+        assert!(check_image(&png).is_ok());
+        assert!(check_image(&[0xFF, 0xD8, 0xFF, 0xE0]).is_ok());
+        // 不依据扩展名，GIF与空数据均不接受
+        for data in [&b"GIF89a"[..], &[]] {
+            assert!(matches!(
+                check_image(data),
+                Err(WecomError::InvalidArgument(_))
+            ));
+        }
+        let mut large = vec![0xFF, 0xD8, 0xFF];
+        large.resize(MAX_IMAGE_BYTES + 1, 0);
+        assert!(matches!(
+            check_image(&large),
+            Err(WecomError::ContentTooLong {
+                field: "image",
+                unit: LengthUnit::Bytes,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_content_disposition_filename() {
+        assert_eq!(
+            content_disposition_filename("attachment; filename=\"MEDIA_ID.jpg\""),
+            Some("MEDIA_ID.jpg".to_string())
+        );
+        assert_eq!(
+            content_disposition_filename(
+                "attachment; filename=\"a.txt\"; filename*=UTF-8''%E6%96%87%E4%BB%B6.txt"
+            ),
+            Some("文件.txt".to_string())
+        );
+        assert_eq!(content_disposition_filename("attachment"), None);
+        assert_eq!(
+            content_disposition_filename("attachment; filename=\"\""),
+            None
+        );
+    }
+
+    #[test]
+    fn test_media_cache_find_by_hash() {
+        let t0 = UNIX_EPOCH + Duration::from_secs(1_380_000_000);
+        let hash = content_hash(b"hello");
+        assert_eq!(hash, "5d41402abc4b2a76b9719d911017c592");
+        let mut cache = MediaCache::default();
+        cache.insert_with_hash(
+            TemporaryMedia::new("a".to_string(), t0, MediaType::Image),
+            hash.clone(),
+            t0,
+        );
+        assert_eq!(cache.find_by_hash(&hash, t0).unwrap().media_id(), "a");
+        assert!(cache.find_by_hash(&content_hash(b"world"), t0).is_none());
+        assert!(cache
+            .find_by_hash(&hash, t0 + TEMPORARY_MEDIA_TTL)
+            .is_none());
+
+        // 素材失效并被清理后，对应的md5记录一并清理
+        let t1 = t0 + TEMPORARY_MEDIA_TTL;
+        cache.insert(
+            TemporaryMedia::new("b".to_string(), t1, MediaType::File),
+            t1,
+        );
+        assert!(cache.hashes.is_empty());
+    }
+
+    #[test]
+    fn test_media_cache_prunes_expired() {
+        let t0 = UNIX_EPOCH + Duration::from_secs(1_380_000_000);
+        let mut cache = MediaCache::default();
+        cache.insert(
+            TemporaryMedia::new("a".to_string(), t0, MediaType::File),
+            t0,
+        );
+        cache.insert(
+            TemporaryMedia::new("b".to_string(), t0 + TEMPORARY_MEDIA_TTL, MediaType::File),
+            t0 + TEMPORARY_MEDIA_TTL,
+        );
+        let ids: Vec<String> = cache
+            .list()
+            .iter()
+            .map(|m| m.media_id().to_string())
+            .collect();
+        assert_eq!(ids, vec!["b".to_string()]);
+    }
+}
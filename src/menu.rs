@@ -0,0 +1,370 @@
+//! 应用菜单
+//!
+//! 菜单最多3个一级菜单，每个一级菜单可含最多5个二级菜单。含二级菜单的一级菜单本身不响应点击。
+
+use crate::message::check_bytes;
+use crate::WecomError;
+use serde::{Deserialize, Deserializer, Serialize};
+
+// 一级与二级菜单的个数上限
+const MAX_MENU_BUTTONS: usize = 3;
+const MAX_MENU_SUB_BUTTONS: usize = 5;
+/// 一级菜单名称的字节数上限
+pub const MAX_MENU_NAME_BYTES: usize = 16;
+/// 二级菜单名称的字节数上限
+pub const MAX_MENU_SUB_NAME_BYTES: usize = 40;
+/// 菜单`key`的字节数上限
+pub const MAX_MENU_KEY_BYTES: usize = 128;
+/// 菜单链接的字节数上限
+pub const MAX_MENU_URL_BYTES: usize = 1024;
+
+// 创建菜单的请求
+// {
+//     "button": [
+//         {"type": "click", "name": "今日歌曲", "key": "V1001_TODAY_MUSIC"},
+//         {
+//             "name": "菜单",
+//             "sub_button": [
+//                 {"type": "view", "name": "搜索", "url": "http://www.soso.com/"},
+//                 {"type": "click", "name": "赞一下我们", "key": "V1001_GOOD"}
+//             ]
+//         }
+//     ]
+// }
+/// 应用的底部菜单
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Menu {
+    pub button: Vec<MenuButton>,
+}
+
+impl Menu {
+    pub fn new(button: Vec<MenuButton>) -> Self {
+        Self { button }
+    }
+
+    // 检查菜单个数、层级，以及名称、key与链接的长度
+    pub(crate) fn validate(&self) -> Result<(), WecomError> {
+        if self.button.is_empty() || self.button.len() > MAX_MENU_BUTTONS {
+            return Err(WecomError::InvalidArgument(format!(
+                "一级菜单须为1到{MAX_MENU_BUTTONS}个，实际{}个",
+                self.button.len()
+            )));
+        }
+        for button in &self.button {
+            check_name("menu.button.name", &button.name, MAX_MENU_NAME_BYTES)?;
+            match (&button.action, button.sub_button.len()) {
+                (None, 0) => {
+                    return Err(WecomError::InvalidArgument(format!(
+                        "菜单{}须指定响应动作或二级菜单",
+                        button.name
+                    )))
+                }
+                (Some(_), n) if n > 0 => {
+                    return Err(WecomError::InvalidArgument(format!(
+                        "菜单{}不可同时指定响应动作与二级菜单",
+                        button.name
+                    )))
+                }
+                (Some(action), _) => action.validate()?,
+                (None, n) if n > MAX_MENU_SUB_BUTTONS => {
+                    return Err(WecomError::InvalidArgument(format!(
+                        "菜单{}的二级菜单最多{MAX_MENU_SUB_BUTTONS}个，实际{n}个",
+                        button.name
+                    )))
+                }
+                (None, _) => {
+                    for sub in &button.sub_button {
+                        check_name("menu.sub_button.name", &sub.name, MAX_MENU_SUB_NAME_BYTES)?;
+                        let Some(action) = &sub.action else {
+                            return Err(WecomError::InvalidArgument(format!(
+                                "二级菜单{}须指定响应动作，且不可再含子菜单",
+                                sub.name
+                            )));
+                        };
+                        if !sub.sub_button.is_empty() {
+                            return Err(WecomError::InvalidArgument(format!(
+                                "二级菜单{}不可再含子菜单",
+                                sub.name
+                            )));
+                        }
+                        action.validate()?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// 菜单名称不可为空
+fn check_name(field: &'static str, name: &str, limit: usize) -> Result<(), WecomError> {
+    if name.is_empty() {
+        return Err(WecomError::InvalidArgument("菜单名称不可为空".to_string()));
+    }
+    check_bytes(field, name, limit)
+}
+
+/// 菜单项。一级菜单可指定响应动作或二级菜单，二级菜单只能指定响应动作。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MenuButton {
+    pub name: String,
+    /// 点击后的响应动作。含二级菜单的一级菜单为None。
+    #[serde(flatten)]
+    pub action: Option<MenuAction>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_sub_button"
+    )]
+    pub sub_button: Vec<MenuButton>,
+}
+
+impl MenuButton {
+    /// 点击后执行`action`的菜单
+    pub fn new(name: &str, action: MenuAction) -> Self {
+        Self {
+            name: name.to_owned(),
+            action: Some(action),
+            sub_button: Vec::new(),
+        }
+    }
+
+    /// 含二级菜单的一级菜单
+    pub fn with_sub_buttons(name: &str, sub_button: Vec<MenuButton>) -> Self {
+        Self {
+            name: name.to_owned(),
+            action: None,
+            sub_button,
+        }
+    }
+
+    /// 点击后推送`key`的事件
+    pub fn click(name: &str, key: &str) -> Self {
+        Self::new(
+            name,
+            MenuAction::Click {
+                key: key.to_owned(),
+            },
+        )
+    }
+
+    /// 点击后打开`url`
+    pub fn view(name: &str, url: &str) -> Self {
+        Self::new(
+            name,
+            MenuAction::View {
+                url: url.to_owned(),
+            },
+        )
+    }
+}
+
+/// 菜单的响应动作，对应菜单的`type`字段
+///
+/// 除`View`与`ViewMiniprogram`外，成员点击后企业微信将带`key`的事件推送至应用的回调地址。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MenuAction {
+    /// 点击推送事件
+    Click { key: String },
+    /// 跳转至链接
+    View { url: String },
+    /// 扫码推送事件
+    ScancodePush { key: String },
+    /// 扫码推送事件，并弹出“消息接收中”提示框
+    ScancodeWaitmsg { key: String },
+    /// 弹出系统拍照发图
+    PicSysphoto { key: String },
+    /// 弹出拍照或者相册发图
+    PicPhotoOrAlbum { key: String },
+    /// 弹出企业微信相册发图器
+    PicWeixin { key: String },
+    /// 弹出地理位置选择器
+    LocationSelect { key: String },
+    /// 跳转至小程序，`appid`须为已关联的小程序
+    ViewMiniprogram { pagepath: String, appid: String },
+}
+
+impl MenuAction {
+    fn validate(&self) -> Result<(), WecomError> {
+        match self {
+            MenuAction::View { url } => check_bytes("menu.url", url, MAX_MENU_URL_BYTES),
+            MenuAction::ViewMiniprogram { .. } => Ok(()),
+            MenuAction::Click { key }
+            | MenuAction::ScancodePush { key }
+            | MenuAction::ScancodeWaitmsg { key }
+            | MenuAction::PicSysphoto { key }
+            | MenuAction::PicPhotoOrAlbum { key }
+            | MenuAction::PicWeixin { key }
+            | MenuAction::LocationSelect { key } => {
+                check_bytes("menu.key", key, MAX_MENU_KEY_BYTES)
+            }
+        }
+    }
+}
+
+// 获取菜单时，二级菜单可能为数组或`{"list": [...]}`，无二级菜单时为空数组
+fn deserialize_sub_button<'de, D>(deserializer: D) -> Result<Vec<MenuButton>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum SubButton {
+        List(Vec<MenuButton>),
+        Wrapped { list: Vec<MenuButton> },
+    }
+    Ok(match SubButton::deserialize(deserializer)? {
+        SubButton::List(list) | SubButton::Wrapped { list } => list,
+    })
+}
+
+// 获取菜单的返回结果。菜单可能直接位于顶层，也可能包在`menu`中。
+// {
+//     "errcode": 0,
+//     "errmsg": "ok",
+//     "button": [
+//         {"type": "click", "name": "今日歌曲", "key": "V1001_TODAY_MUSIC", "sub_button": []}
+//     ]
+// }
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum MenuResponse {
+    Wrapped { menu: Menu },
+    Flat(Menu),
+}
+
+impl From<MenuResponse> for Menu {
+    fn from(response: MenuResponse) -> Self {
+        match response {
+            MenuResponse::Wrapped { menu } | MenuResponse::Flat(menu) => menu,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_menu() -> Menu {
+        Menu::new(vec![
+            MenuButton::click("今日歌曲", "V1001_TODAY_MUSIC"),
+            MenuButton::with_sub_buttons(
+                "菜单",
+                vec![
+                    MenuButton::view("搜索", "http://www.soso.com/"),
+                    MenuButton::new(
+                        "扫码",
+                        MenuAction::ScancodePush {
+                            key: "rselfmenu_0_1".to_string(),
+                        },
+                    ),
+                    MenuButton::new(
+                        "拍照",
+                        MenuAction::PicSysphoto {
+                            key: "rselfmenu_1_0".to_string(),
+                        },
+                    ),
+                ],
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_serialize_menu() {
+        let menu = sample_menu();
+        menu.validate().unwrap();
+        assert_eq!(
+            serde_json::to_value(&menu).unwrap(),
+            json!({
+                "button": [
+                    {"type": "click", "name": "今日歌曲", "key": "V1001_TODAY_MUSIC"},
+                    {
+                        "name": "菜单",
+                        "sub_button": [
+                            {"type": "view", "name": "搜索", "url": "http://www.soso.com/"},
+                            {"type": "scancode_push", "name": "扫码", "key": "rselfmenu_0_1"},
+                            {"type": "pic_sysphoto", "name": "拍照", "key": "rselfmenu_1_0"}
+                        ]
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_deserialize_menu_response() {
+        // 无二级菜单时为空数组，二级菜单也可能包在list中
+        let response: MenuResponse = serde_json::from_value(json!({
+            "errcode": 0,
+            "errmsg": "ok",
+            "button": [
+                {"type": "click", "name": "今日歌曲", "key": "V1001_TODAY_MUSIC", "sub_button": []},
+                {
+                    "name": "菜单",
+                    "sub_button": {"list": [
+                        {"type": "view", "name": "搜索", "url": "http://www.soso.com/", "sub_button": []},
+                        {"type": "scancode_push", "name": "扫码", "key": "rselfmenu_0_1"},
+                        {"type": "pic_sysphoto", "name": "拍照", "key": "rselfmenu_1_0"}
+                    ]}
+                }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(Menu::from(response), sample_menu());
+
+        let response: MenuResponse =
+            serde_json::from_value(json!({"menu": serde_json::to_value(sample_menu()).unwrap()}))
+                .unwrap();
+        assert_eq!(Menu::from(response), sample_menu());
+    }
+
+    #[test]
+    fn test_validate_menu() {
+        let click = |name: &str| MenuButton::click(name, "KEY");
+        assert!(Menu::new(vec![]).validate().is_err());
+        assert!(
+            Menu::new(vec![click("a"), click("b"), click("c"), click("d")])
+                .validate()
+                .is_err()
+        );
+
+        let subs = |n: usize| (0..n).map(|i| click(&i.to_string())).collect::<Vec<_>>();
+        assert!(
+            Menu::new(vec![MenuButton::with_sub_buttons("菜单", subs(5))])
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            Menu::new(vec![MenuButton::with_sub_buttons("菜单", subs(6))])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            Menu::new(vec![MenuButton::with_sub_buttons("菜单", vec![])])
+                .validate()
+                .is_err()
+        );
+
+        // 三级菜单
+        let nested = MenuButton::with_sub_buttons("二级", vec![click("三级")]);
+        assert!(
+            Menu::new(vec![MenuButton::with_sub_buttons("菜单", vec![nested])])
+                .validate()
+                .is_err()
+        );
+
+        // 一级菜单名称最多16字节，二级最多40字节
+        assert!(Menu::new(vec![click("一二三四五")]).validate().is_ok());
+        let Err(WecomError::ContentTooLong { field, .. }) =
+            Menu::new(vec![click("一二三四五六")]).validate()
+        else {
+            panic!("Top-level name longer than 16 bytes should be rejected");
+        };
+        assert_eq!(field, "menu.button.name");
+        let long_sub = MenuButton::with_sub_buttons("菜单", vec![click(&"子".repeat(14))]);
+        assert!(Menu::new(vec![long_sub]).validate().is_err());
+        assert!(Menu::new(vec![click("")]).validate().is_err());
+    }
+}
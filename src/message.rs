@@ -1,10 +1,14 @@
-use crate::error::Error;
+use crate::error::{LengthUnit, WecomError};
 use serde::Serialize;
 use serde_json::{json, Value};
 
 pub trait WecomMessage {
     fn msg_type(&self) -> MessageType;
     fn key(&self) -> String;
+    /// 检查消息内容是否符合企业微信的限制，由`MessageBuilder::build()`调用。
+    fn validate(&self) -> Result<(), WecomError> {
+        Ok(())
+    }
     fn value(&self) -> impl Serialize
     where
         Self: Serialize,
@@ -23,6 +27,28 @@ pub enum MessageType {
     TextCard,
     News,
     Markdown,
+    TemplateCard,
+    Location,
+    OA,
+}
+
+impl MessageType {
+    /// 消息中msgtype字段的取值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageType::Audio => "voice",
+            MessageType::File => "file",
+            MessageType::Image => "image",
+            MessageType::Markdown => "markdown",
+            MessageType::News => "news",
+            MessageType::Text => "text",
+            MessageType::TextCard => "textcard",
+            MessageType::Video => "video",
+            MessageType::TemplateCard => "template_card",
+            MessageType::Location => "location",
+            MessageType::OA => "oa",
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -31,9 +57,9 @@ pub struct MessageBuilder {
     groups: Option<String>,
     tags: Option<String>,
     agent_id: Option<usize>,
-    safe: i64,
-    enable_id_trans: i64,
-    enable_duplicate_check: i64,
+    safe: bool,
+    enable_id_trans: bool,
+    enable_duplicate_check: bool,
     duplicate_check_interval: usize,
 }
 
@@ -44,9 +70,9 @@ impl Default for MessageBuilder {
             groups: None,
             tags: None,
             agent_id: None,
-            safe: 0,
-            enable_id_trans: 0,
-            enable_duplicate_check: 0,
+            safe: false,
+            enable_id_trans: false,
+            enable_duplicate_check: false,
             duplicate_check_interval: 1800,
         }
     }
@@ -58,34 +84,17 @@ impl MessageBuilder {
     }
 
     pub fn to_users(mut self, users: Vec<&str>) -> Self {
-        self.users = Some(
-            users
-                .iter()
-                .fold("".to_string(), |acc, &u| format!("{acc}|{u}"))
-                .trim_start_matches('|')
-                .to_string(),
-        );
+        self.users = Some(users.join("|"));
         self
     }
 
     pub fn to_groups(mut self, groups: Vec<&str>) -> Self {
-        self.groups = Some(
-            groups
-                .iter()
-                .fold("".to_string(), |acc, &u| format!("{acc}|{u}"))
-                .trim_start_matches('|')
-                .to_string(),
-        );
+        self.groups = Some(groups.join("|"));
         self
     }
 
     pub fn to_tags(mut self, tags: Vec<&str>) -> Self {
-        self.tags = Some(
-            tags.iter()
-                .fold("".to_string(), |acc, &u| format!("{acc}|{u}"))
-                .trim_start_matches('|')
-                .to_string(),
-        );
+        self.tags = Some(tags.join("|"));
         self
     }
 
@@ -94,16 +103,19 @@ impl MessageBuilder {
         self
     }
 
-    pub fn with_safe(mut self, safe: i64) -> Self {
+    /// 是否为保密消息。保密消息不能分享，且内容显示水印。
+    pub fn with_safe(mut self, safe: bool) -> Self {
         self.safe = safe;
         self
     }
-    pub fn with_enable_id_trans(mut self, enable_id_trans: i64) -> Self {
-        self.enable_id_trans = enable_id_trans;
+    /// 是否开启id转译
+    pub fn with_enable_id_trans(mut self, enable: bool) -> Self {
+        self.enable_id_trans = enable;
         self
     }
-    pub fn with_enable_duplicate_check(mut self, enable_duplicate_check: i64) -> Self {
-        self.enable_duplicate_check = enable_duplicate_check;
+    /// 是否开启重复消息检查
+    pub fn with_enable_duplicate_check(mut self, enable: bool) -> Self {
+        self.enable_duplicate_check = enable;
         self
     }
     pub fn with_duplicate_check_interval(mut self, duplicate_check_interval: usize) -> Self {
@@ -111,7 +123,7 @@ impl MessageBuilder {
         self
     }
 
-    pub fn build<T>(&self, content: T) -> Result<Value, Box<dyn std::error::Error>>
+    pub fn build<T>(&self, content: T) -> Result<Value, WecomError>
     where
         T: Serialize + WecomMessage,
     {
@@ -119,38 +131,56 @@ impl MessageBuilder {
             .iter()
             .all(|&x| x.is_none())
         {
-            return Err(Box::new(Error::new(-999, "收件人不可为空".to_string())));
+            return Err(WecomError::Build("收件人不可为空".to_string()));
         }
 
         if self.agent_id.is_none() {
-            return Err(Box::new(Error::new(-999, "AgentID不可为空".to_string())));
+            return Err(WecomError::Build("AgentID不可为空".to_string()));
         }
 
+        content.validate()?;
+
         let empty_string = "".to_string();
         let mut j = json!({
             "touser": self.users.clone().unwrap_or(empty_string.clone()),
             "toparty": self.groups.clone().unwrap_or(empty_string.clone()),
             "totag": self.tags.clone().unwrap_or(empty_string.clone()),
-            "msgtype": match content.msg_type() {
-                MessageType::Audio => "voice".to_string(),
-                MessageType::File => "file".to_string(),
-                MessageType::Image => "image".to_string(),
-                MessageType::Markdown => "markdown".to_string(),
-                MessageType::News => "news".to_string(),
-                MessageType::Text => "text".to_string(),
-                MessageType::TextCard => "textcard".to_string(),
-                MessageType::Video => "video".to_string(),
-            },
+            "msgtype": content.msg_type().as_str(),
             "agentid": self.agent_id.expect("AgentID should not be None"),
-            "safe": self.safe,
-            "enable_id_trans": self.enable_id_trans,
-            "enable_duplicate_check": self.enable_duplicate_check,
+            // 企业微信只接受0或1
+            "safe": i64::from(self.safe),
+            "enable_id_trans": i64::from(self.enable_id_trans),
+            "enable_duplicate_check": i64::from(self.enable_duplicate_check),
             "duplicate_check_interval": self.duplicate_check_interval,});
         j.as_object_mut()
             .unwrap()
             .insert(content.key(), serde_json::to_value(content.value())?);
         Ok(j)
     }
+
+    /// 与`build()`相同，但消耗构造器，便于链式调用。
+    pub fn build_owned<T>(self, content: T) -> Result<Value, WecomError>
+    where
+        T: Serialize + WecomMessage,
+    {
+        self.build(content)
+    }
+
+    /// 构造消息并输出为格式化的JSON字符串，便于调试。
+    pub fn build_json_string<T>(&self, content: T) -> Result<String, WecomError>
+    where
+        T: Serialize + WecomMessage,
+    {
+        Ok(serde_json::to_string_pretty(&self.build(content)?)?)
+    }
+
+    /// 构造消息并输出为JSON字节序列。
+    pub fn build_json_bytes<T>(&self, content: T) -> Result<Vec<u8>, WecomError>
+    where
+        T: Serialize + WecomMessage,
+    {
+        Ok(serde_json::to_vec(&self.build(content)?)?)
+    }
 }
 
 // 文本消息
@@ -180,6 +210,9 @@ impl Text {
     }
 }
 
+/// 文本消息内容的字节数上限
+pub const MAX_TEXT_BYTES: usize = 2048;
+
 impl WecomMessage for Text {
     fn msg_type(&self) -> MessageType {
         MessageType::Text
@@ -188,6 +221,11 @@ impl WecomMessage for Text {
     fn key(&self) -> String {
         "text".to_string()
     }
+
+    // 文本消息的上限按字节计
+    fn validate(&self) -> Result<(), WecomError> {
+        check_bytes("text.content", &self.content, MAX_TEXT_BYTES)
+    }
 }
 
 // 图片消息
@@ -205,7 +243,27 @@ impl WecomMessage for Text {
 //    "enable_duplicate_check": 0,
 //    "duplicate_check_interval": 1800
 // }
-pub struct ImageMsg {}
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ImageMsg {
+    media_id: String,
+}
+
+impl ImageMsg {
+    /// `media_id`为上传图片类型的临时素材所得
+    pub fn new(media_id: String) -> Self {
+        Self { media_id }
+    }
+}
+
+impl WecomMessage for ImageMsg {
+    fn msg_type(&self) -> MessageType {
+        MessageType::Image
+    }
+
+    fn key(&self) -> String {
+        "image".to_string()
+    }
+}
 
 // 语音消息
 // 示例
@@ -257,7 +315,27 @@ pub struct VideoMsg {}
 //     "enable_duplicate_check": 0,
 //     "duplicate_check_interval": 1800
 // }
-pub struct FileMsg {}
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FileMsg {
+    media_id: String,
+}
+
+impl FileMsg {
+    /// `media_id`为上传临时素材所得
+    pub fn new(media_id: String) -> Self {
+        Self { media_id }
+    }
+}
+
+impl WecomMessage for FileMsg {
+    fn msg_type(&self) -> MessageType {
+        MessageType::File
+    }
+
+    fn key(&self) -> String {
+        "file".to_string()
+    }
+}
 
 // 文本卡片消息
 // 示例
@@ -277,7 +355,223 @@ pub struct FileMsg {}
 //     "enable_duplicate_check": 0,
 //     "duplicate_check_interval": 1800
 // }
-pub struct TextCardMsg {}
+#[derive(Debug, Serialize, PartialEq)]
+pub struct TextCardMsg {
+    title: String,
+    description: String,
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    btntxt: Option<String>,
+}
+
+impl TextCardMsg {
+    pub fn new(title: String, description: String, url: String) -> Self {
+        Self {
+            title,
+            description,
+            url,
+            btntxt: None,
+        }
+    }
+
+    /// 按钮文字，最多4个字符。未设置时企业微信显示为“详情”。
+    pub fn with_btntxt(mut self, btntxt: String) -> Self {
+        self.btntxt = Some(btntxt);
+        self
+    }
+}
+
+/// 文本卡片标题的字符数上限
+pub const MAX_TEXTCARD_TITLE_CHARS: usize = 128;
+/// 文本卡片描述的字符数上限
+pub const MAX_TEXTCARD_DESCRIPTION_CHARS: usize = 512;
+/// 文本卡片链接的字节数上限
+pub const MAX_TEXTCARD_URL_BYTES: usize = 512;
+/// 文本卡片按钮文字的字符数上限
+pub const MAX_TEXTCARD_BTNTXT_CHARS: usize = 4;
+
+impl WecomMessage for TextCardMsg {
+    fn msg_type(&self) -> MessageType {
+        MessageType::TextCard
+    }
+
+    fn key(&self) -> String {
+        "textcard".to_string()
+    }
+
+    // 标题、描述与按钮文字按字符计，链接按字节计
+    fn validate(&self) -> Result<(), WecomError> {
+        check_length("textcard.title", &self.title, MAX_TEXTCARD_TITLE_CHARS)?;
+        check_length(
+            "textcard.description",
+            &self.description,
+            MAX_TEXTCARD_DESCRIPTION_CHARS,
+        )?;
+        if let Some(btntxt) = &self.btntxt {
+            check_length("textcard.btntxt", btntxt, MAX_TEXTCARD_BTNTXT_CHARS)?;
+        }
+        check_bytes("textcard.url", &self.url, MAX_TEXTCARD_URL_BYTES)
+    }
+}
+
+// 图文消息
+// 示例
+// {
+//    "touser" : "UserID1|UserID2|UserID3",
+//    "toparty" : "PartyID1 | PartyID2",
+//    "totag" : "TagID1 | TagID2",
+//    "msgtype" : "news",
+//    "agentid" : 1,
+//    "news" : {
+//        "articles" : [
+//            {
+//                "title" : "中秋节礼品领取",
+//                "description" : "今年中秋节公司有豪礼相送",
+//                "url" : "URL",
+//                "picurl" : "http://res.mail.qq.com/node/ww/wwopenmng/images/independent/doc/test_pic_msg1.png"
+//            }
+//         ]
+//    },
+//    "enable_id_trans": 0,
+//    "enable_duplicate_check": 0,
+//    "duplicate_check_interval": 1800
+// }
+#[derive(Debug, Serialize, PartialEq)]
+pub struct NewsMsg {
+    articles: Vec<NewsArticle>,
+}
+
+/// 图文消息的文章数量上限
+pub const MAX_NEWS_ARTICLES: usize = 8;
+/// 文章标题的字符数上限
+pub const MAX_NEWS_TITLE_CHARS: usize = 128;
+/// 文章描述的字符数上限
+pub const MAX_NEWS_DESCRIPTION_CHARS: usize = 512;
+
+impl NewsMsg {
+    /// 文章数须在1到8篇之间，每篇的`url`与`picurl`须为有效的HTTPS地址，
+    /// 标题与描述分别不超过128与512个字符。
+    pub fn new(articles: Vec<NewsArticle>) -> Result<Self, WecomError> {
+        if !(1..=MAX_NEWS_ARTICLES).contains(&articles.len()) {
+            return Err(WecomError::InvalidArgument(
+                "news requires 1–8 articles".to_string(),
+            ));
+        }
+        for article in &articles {
+            // 企业微信按字符而非字节计数
+            check_length("news.title", &article.title, MAX_NEWS_TITLE_CHARS)?;
+            if let Some(description) = &article.description {
+                check_length("news.description", description, MAX_NEWS_DESCRIPTION_CHARS)?;
+            }
+            check_https(&article.url)?;
+            if let Some(picurl) = &article.picurl {
+                check_https(picurl)?;
+            }
+        }
+        Ok(Self { articles })
+    }
+}
+
+// 链接须为有效的HTTPS地址
+pub(crate) fn check_https(url: &str) -> Result<(), WecomError> {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if parsed.scheme() == "https" => Ok(()),
+        _ => Err(WecomError::InvalidUrl(url.to_string())),
+    }
+}
+
+// 按字符数检查长度
+pub(crate) fn check_length(
+    field: &'static str,
+    value: &str,
+    limit: usize,
+) -> Result<(), WecomError> {
+    let actual = value.chars().count();
+    if actual > limit {
+        return Err(WecomError::ContentTooLong {
+            field,
+            actual,
+            limit,
+            unit: LengthUnit::Chars,
+        });
+    }
+    Ok(())
+}
+
+// 按UTF-8字节数检查长度
+pub(crate) fn check_bytes(
+    field: &'static str,
+    value: &str,
+    limit: usize,
+) -> Result<(), WecomError> {
+    if value.len() > limit {
+        return Err(WecomError::ContentTooLong {
+            field,
+            actual: value.len(),
+            limit,
+            unit: LengthUnit::Bytes,
+        });
+    }
+    Ok(())
+}
+
+impl WecomMessage for NewsMsg {
+    fn msg_type(&self) -> MessageType {
+        MessageType::News
+    }
+
+    fn key(&self) -> String {
+        "news".to_string()
+    }
+}
+
+/// 图文消息中的一篇文章
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct NewsArticle {
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    picurl: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    appid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pagepath: Option<String>,
+}
+
+impl NewsArticle {
+    pub fn new(title: String, url: String) -> Self {
+        Self {
+            title,
+            description: None,
+            url,
+            picurl: None,
+            appid: None,
+            pagepath: None,
+        }
+    }
+
+    /// 文章描述，不超过512个字符
+    pub fn with_description(mut self, description: String) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    /// 图片链接，须为HTTPS地址，支持JPG、PNG格式。可先以`WecomAgent::upload_image()`上传获得。
+    pub fn with_picurl(mut self, picurl: String) -> Self {
+        self.picurl = Some(picurl);
+        self
+    }
+
+    /// 点击后打开小程序。`pagepath`为小程序内的页面路径，缺省时打开首页。
+    /// 不支持小程序的客户端仍打开`url`。
+    pub fn with_miniprogram(mut self, appid: &str, pagepath: Option<&str>) -> Self {
+        self.appid = Some(appid.to_owned());
+        self.pagepath = pagepath.map(str::to_owned);
+        self
+    }
+}
 
 // MarkDown消息
 // 示例
@@ -293,13 +587,829 @@ pub struct TextCardMsg {}
 //     "enable_duplicate_check": 0,
 //     "duplicate_check_interval": 1800
 // }
-pub struct MarkDownMsg {}
+#[derive(Debug, Serialize, PartialEq)]
+pub struct MarkDownMsg {
+    content: String,
+}
+
+impl MarkDownMsg {
+    pub fn new(content: String) -> Self {
+        Self { content }
+    }
+}
+
+/// MarkDown中`<font>`标签可用的颜色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkdownColor {
+    /// 绿色
+    Info,
+    /// 灰色
+    Comment,
+    /// 橙红色
+    Warning,
+}
+
+impl MarkdownColor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarkdownColor::Info => "info",
+            MarkdownColor::Comment => "comment",
+            MarkdownColor::Warning => "warning",
+        }
+    }
+}
+
+/// 逐段拼接企业微信MarkDown消息的内容。内容原样写入，不做转义。
+///
+/// ```rust
+/// use wecom_agent::message::{MarkdownBuilder, MarkdownColor};
+///
+/// let msg = MarkdownBuilder::new()
+///     .bold("发布完成")
+///     .newline()
+///     .text("状态：")
+///     .color("成功", MarkdownColor::Info)
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownBuilder {
+    buffer: String,
+}
+
+impl MarkdownBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(&mut self, content: &str) -> &mut Self {
+        self.buffer.push_str(content);
+        self
+    }
+
+    /// `**content**`
+    pub fn bold(&mut self, content: &str) -> &mut Self {
+        self.buffer.push_str(&format!("**{content}**"));
+        self
+    }
+
+    /// `*content*`
+    pub fn italic(&mut self, content: &str) -> &mut Self {
+        self.buffer.push_str(&format!("*{content}*"));
+        self
+    }
+
+    /// `[text](url)`
+    pub fn link(&mut self, text: &str, url: &str) -> &mut Self {
+        self.buffer.push_str(&format!("[{text}]({url})"));
+        self
+    }
+
+    /// `<font color="...">text</font>`
+    pub fn color(&mut self, text: &str, color: MarkdownColor) -> &mut Self {
+        self.buffer
+            .push_str(&format!("<font color=\"{}\">{text}</font>", color.as_str()));
+        self
+    }
+
+    pub fn newline(&mut self) -> &mut Self {
+        self.buffer.push('\n');
+        self
+    }
+
+    /// 独占数行的代码块。前面的内容未换行时先换行。
+    pub fn code_block(&mut self, lang: &str, code: &str) -> &mut Self {
+        if !self.buffer.is_empty() && !self.buffer.ends_with('\n') {
+            self.buffer.push('\n');
+        }
+        self.buffer.push_str(&format!(
+            "```{lang}\n{}\n```\n",
+            code.trim_end_matches('\n')
+        ));
+        self
+    }
+
+    /// 生成消息内容。长度在`MessageBuilder::build()`时检查。
+    pub fn build(&self) -> MarkDownMsg {
+        MarkDownMsg::new(self.buffer.clone())
+    }
+}
+
+/// MarkDown消息内容的字节数上限
+pub const MAX_MARKDOWN_BYTES: usize = 4096;
+
+impl WecomMessage for MarkDownMsg {
+    fn msg_type(&self) -> MessageType {
+        MessageType::Markdown
+    }
+
+    fn key(&self) -> String {
+        "markdown".to_string()
+    }
+
+    // 与文本消息相同，上限按字节计
+    fn validate(&self) -> Result<(), WecomError> {
+        check_bytes("markdown.content", &self.content, MAX_MARKDOWN_BYTES)
+    }
+}
+
+// 模板卡片消息
+// 示例
+// {
+//     "touser" : "UserID1|UserID2|UserID3",
+//     "msgtype" : "template_card",
+//     "agentid" : 1,
+//     "template_card" : {
+//         "card_type" : "button_interaction",
+//         "main_title" : {"title" : "请假审批", "desc" : "张三申请年假1天"},
+//         "task_id": "task_id",
+//         "button_list": [{"text": "同意", "style": 1, "key": "approve"}]
+//     },
+//     "enable_id_trans": 0,
+//     "enable_duplicate_check": 0,
+//     "duplicate_check_interval": 1800
+// }
+/// 模板卡片。卡片类型与字段繁多，直接以JSON对象表示，须包含`card_type`。
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(transparent)]
+pub struct TemplateCard(Value);
+
+impl TemplateCard {
+    pub fn new(card: Value) -> Self {
+        Self(card)
+    }
+}
+
+impl WecomMessage for TemplateCard {
+    fn msg_type(&self) -> MessageType {
+        MessageType::TemplateCard
+    }
+
+    fn key(&self) -> String {
+        "template_card".to_string()
+    }
+}
+
+// 位置消息
+// 示例
+// {
+//     "touser" : "UserID1|UserID2|UserID3",
+//     "msgtype" : "location",
+//     "agentid" : 1,
+//     "location" : {
+//         "latitude" : 23.134521,
+//         "longitude" : 113.358803,
+//         "scale" : 15,
+//         "label" : "广州市天河区"
+//     }
+// }
+/// 位置消息
+#[derive(Debug, Serialize, PartialEq)]
+pub struct LocationMsg {
+    latitude: f64,
+    longitude: f64,
+    scale: u32,
+    label: String,
+}
+
+impl LocationMsg {
+    /// `scale`为地图缩放级别，取值3至15
+    pub fn new(latitude: f64, longitude: f64, scale: u32, label: String) -> Self {
+        Self {
+            latitude,
+            longitude,
+            scale,
+            label,
+        }
+    }
+}
+
+// 地图缩放级别的范围
+const LOCATION_SCALE: std::ops::RangeInclusive<u32> = 3..=15;
+
+impl WecomMessage for LocationMsg {
+    fn msg_type(&self) -> MessageType {
+        MessageType::Location
+    }
+
+    fn key(&self) -> String {
+        "location".to_string()
+    }
+
+    // 纬度、经度与缩放级别须在有效范围内
+    fn validate(&self) -> Result<(), WecomError> {
+        if !(-90.0..=90.0).contains(&self.latitude) {
+            return Err(WecomError::InvalidArgument(format!(
+                "纬度须在-90至90之间: {}",
+                self.latitude
+            )));
+        }
+        if !(-180.0..=180.0).contains(&self.longitude) {
+            return Err(WecomError::InvalidArgument(format!(
+                "经度须在-180至180之间: {}",
+                self.longitude
+            )));
+        }
+        if !LOCATION_SCALE.contains(&self.scale) {
+            return Err(WecomError::InvalidArgument(format!(
+                "缩放级别须在{}至{}之间: {}",
+                LOCATION_SCALE.start(),
+                LOCATION_SCALE.end(),
+                self.scale
+            )));
+        }
+        Ok(())
+    }
+}
+
+// OA审批通知消息
+// 示例
+// {
+//     "touser" : "UserID1|UserID2",
+//     "msgtype" : "oa",
+//     "agentid" : 1,
+//     "oa" : {
+//         "head" : {"bgcolor" : "BLUE", "text" : "请假审批"},
+//         "body" : {"content" : "申请人：张三\n请假类型：年假\n天数：1天"},
+//         "url" : "URL",
+//         "pc_url" : "URL"
+//     }
+// }
+/// OA审批通知的标题栏
+#[derive(Debug, Serialize, PartialEq)]
+pub struct OAMsgHead {
+    bgcolor: String,
+    text: String,
+}
+
+impl OAMsgHead {
+    /// `bgcolor`为标题栏颜色，可选`BLUE`、`RED`、`YELLOW`、`GREEN`、`GRAY`
+    pub fn new(bgcolor: String, text: String) -> Self {
+        Self { bgcolor, text }
+    }
+}
+
+/// OA审批通知的正文，可包含多行
+#[derive(Debug, Serialize, PartialEq)]
+pub struct OAMsgBody {
+    content: String,
+}
+
+impl OAMsgBody {
+    pub fn new(content: String) -> Self {
+        Self { content }
+    }
+}
+
+/// OA审批通知消息
+#[derive(Debug, Serialize, PartialEq)]
+pub struct OAMsg {
+    head: OAMsgHead,
+    body: OAMsgBody,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pc_url: Option<String>,
+}
+
+impl OAMsg {
+    pub fn new(head: OAMsgHead, body: OAMsgBody) -> Self {
+        Self {
+            head,
+            body,
+            url: None,
+            pc_url: None,
+        }
+    }
+
+    /// 点击后跳转的链接
+    pub fn with_url(mut self, url: String) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    /// 在电脑端点击后跳转的链接
+    pub fn with_pc_url(mut self, pc_url: String) -> Self {
+        self.pc_url = Some(pc_url);
+        self
+    }
+}
+
+// 标题栏可选的颜色
+const OA_BGCOLORS: [&str; 5] = ["BLUE", "RED", "YELLOW", "GREEN", "GRAY"];
+
+impl WecomMessage for OAMsg {
+    fn msg_type(&self) -> MessageType {
+        MessageType::OA
+    }
+
+    fn key(&self) -> String {
+        "oa".to_string()
+    }
+
+    fn validate(&self) -> Result<(), WecomError> {
+        if !OA_BGCOLORS.contains(&self.head.bgcolor.as_str()) {
+            return Err(WecomError::InvalidArgument(format!(
+                "标题栏颜色须为{}之一: {}",
+                OA_BGCOLORS.join("、"),
+                self.head.bgcolor
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 模板卡片的更新方式，参见`WecomAgent::update_template_card()`
+#[derive(Debug, Clone, PartialEq)]
+pub enum CardUpdate {
+    /// 将按钮置灰，并替换为`replace_name`
+    Button { replace_name: String },
+    /// 替换整张卡片
+    Card(TemplateCard),
+}
+
+// 单次更新模板卡片最多指定的成员数
+const MAX_CARD_UPDATE_USERS: usize = 100;
+
+// 更新模板卡片的请求
+// {
+//     "userids" : ["userid1","userid2"],
+//     "atall" : 0,
+//     "agentid" : 1,
+//     "response_code": "response_code",
+//     "button":{"replace_name": "replace_name"}
+// }
+/// 更新模板卡片的请求，由`UpdateTemplateCardRequest::builder()`构造
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateTemplateCardRequest {
+    userids: Vec<String>,
+    atall: bool,
+    agent_id: usize,
+    response_code: String,
+    update: CardUpdate,
+}
+
+impl UpdateTemplateCardRequest {
+    pub fn builder() -> UpdateTemplateCardBuilder {
+        UpdateTemplateCardBuilder::default()
+    }
+
+    pub fn agent_id(&self) -> usize {
+        self.agent_id
+    }
+
+    pub fn response_code(&self) -> &str {
+        &self.response_code
+    }
+
+    // 请求体
+    pub(crate) fn to_json(&self) -> Value {
+        let mut body = json!({
+            "agentid": self.agent_id,
+            "response_code": self.response_code,
+        });
+        if self.atall {
+            body["atall"] = json!(1);
+        } else {
+            body["userids"] = json!(self.userids);
+        }
+        match &self.update {
+            CardUpdate::Button { replace_name } => {
+                body["button"] = json!({ "replace_name": replace_name });
+            }
+            CardUpdate::Card(card) => {
+                body["template_card"] = json!(card);
+            }
+        }
+        body
+    }
+}
+
+/// `UpdateTemplateCardRequest`的构造器
+#[derive(Debug, Default)]
+pub struct UpdateTemplateCardBuilder {
+    userids: Vec<String>,
+    atall: bool,
+    agent_id: Option<usize>,
+    response_code: Option<String>,
+}
+
+impl UpdateTemplateCardBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 发送模板卡片时`MsgSendResponse::response_code()`返回的值，24小时内有效且只能使用一次
+    pub fn response_code(mut self, response_code: &str) -> Self {
+        self.response_code = Some(response_code.to_owned());
+        self
+    }
+
+    /// 更新指定成员的卡片，最多100个
+    pub fn to_users(mut self, users: Vec<&str>) -> Self {
+        self.userids = users.into_iter().map(str::to_owned).collect();
+        self
+    }
+
+    /// 更新全部接收人的卡片
+    pub fn to_all(mut self) -> Self {
+        self.atall = true;
+        self
+    }
+
+    pub fn from_agent(mut self, agent_id: usize) -> Self {
+        self.agent_id = Some(agent_id);
+        self
+    }
+
+    pub fn build(self, update: CardUpdate) -> Result<UpdateTemplateCardRequest, WecomError> {
+        let Some(response_code) = self.response_code else {
+            return Err(WecomError::Build("response_code不可为空".to_string()));
+        };
+        let Some(agent_id) = self.agent_id else {
+            return Err(WecomError::Build("AgentID不可为空".to_string()));
+        };
+        if !self.atall {
+            check_update_userids(&self.userids, MAX_CARD_UPDATE_USERS)?;
+        } else if !self.userids.is_empty() {
+            return Err(WecomError::Build("userids与atall不可同时指定".to_string()));
+        }
+        Ok(UpdateTemplateCardRequest {
+            userids: self.userids,
+            atall: self.atall,
+            agent_id,
+            response_code,
+            update,
+        })
+    }
+}
+
+// 检查更新卡片时指定的成员：不可为空，且不超过`limit`个
+fn check_update_userids(userids: &[String], limit: usize) -> Result<(), WecomError> {
+    if userids.is_empty() {
+        return Err(WecomError::Build("userids不可为空".to_string()));
+    }
+    if userids.len() > limit {
+        return Err(WecomError::InvalidArgument(format!(
+            "userids最多{limit}个，实际{}个",
+            userids.len()
+        )));
+    }
+    Ok(())
+}
+
+// 单次更新任务卡片最多指定的成员数
+const MAX_TASKCARD_UPDATE_USERS: usize = 1000;
+
+// 更新任务卡片的请求（旧版接口）
+// {
+//     "userids" : ["userid1","userid2"],
+//     "agentid" : 1,
+//     "task_id": "taskid122",
+//     "clicked_key": "key111"
+// }
+/// 更新任务卡片的请求，由`UpdateTaskCardRequest::builder()`构造。任务卡片为旧版消息，新接入请使用模板卡片。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UpdateTaskCardRequest {
+    userids: Vec<String>,
+    #[serde(rename = "agentid")]
+    agent_id: usize,
+    task_id: String,
+    clicked_key: String,
+}
+
+impl UpdateTaskCardRequest {
+    pub fn builder() -> UpdateTaskCardBuilder {
+        UpdateTaskCardBuilder::default()
+    }
+
+    pub fn agent_id(&self) -> usize {
+        self.agent_id
+    }
+
+    pub fn task_id(&self) -> &str {
+        &self.task_id
+    }
+}
+
+/// `UpdateTaskCardRequest`的构造器
+#[derive(Debug, Default)]
+pub struct UpdateTaskCardBuilder {
+    userids: Vec<String>,
+    agent_id: Option<usize>,
+    task_id: Option<String>,
+}
+
+impl UpdateTaskCardBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 更新指定成员的卡片，1至1000个
+    pub fn to_users(mut self, users: Vec<&str>) -> Self {
+        self.userids = users.into_iter().map(str::to_owned).collect();
+        self
+    }
+
+    pub fn from_agent(mut self, agent_id: usize) -> Self {
+        self.agent_id = Some(agent_id);
+        self
+    }
+
+    /// 发送任务卡片时指定的task_id
+    pub fn task_id(mut self, task_id: &str) -> Self {
+        self.task_id = Some(task_id.to_owned());
+        self
+    }
+
+    /// 以被点击按钮的`clicked_key`构造请求，该按钮将显示为已处理
+    pub fn build(self, clicked_key: &str) -> Result<UpdateTaskCardRequest, WecomError> {
+        let Some(task_id) = self.task_id else {
+            return Err(WecomError::Build("task_id不可为空".to_string()));
+        };
+        let Some(agent_id) = self.agent_id else {
+            return Err(WecomError::Build("AgentID不可为空".to_string()));
+        };
+        check_update_userids(&self.userids, MAX_TASKCARD_UPDATE_USERS)?;
+        Ok(UpdateTaskCardRequest {
+            userids: self.userids,
+            agent_id,
+            task_id,
+            clicked_key: clicked_key.to_owned(),
+        })
+    }
+}
 
 #[cfg(test)]
 mod test {
     use std::vec;
 
     use super::*;
+
+    mod proptests {
+        use super::*;
+        use proptest::prelude::*;
+
+        // 含空串、竖线与多字节字符的任意ID
+        fn ids() -> impl Strategy<Value = Vec<String>> {
+            prop::collection::vec(".{0,64}", 0..20)
+        }
+
+        fn as_str(ids: &[String]) -> Vec<&str> {
+            ids.iter().map(String::as_str).collect()
+        }
+
+        fn build(
+            users: &Option<Vec<String>>,
+            groups: &Option<Vec<String>>,
+            tags: &Option<Vec<String>>,
+            agent_id: Option<usize>,
+        ) -> Result<Value, WecomError> {
+            let mut builder = MessageBuilder::default();
+            if let Some(users) = users {
+                builder = builder.to_users(as_str(users));
+            }
+            if let Some(groups) = groups {
+                builder = builder.to_groups(as_str(groups));
+            }
+            if let Some(tags) = tags {
+                builder = builder.to_tags(as_str(tags));
+            }
+            if let Some(agent_id) = agent_id {
+                builder = builder.from_agent(agent_id);
+            }
+            builder.build(Text::new("hello".to_string()))
+        }
+
+        proptest! {
+            #[test]
+            fn test_valid_builder_produces_message(
+                users in ids(),
+                groups in ids(),
+                tags in ids(),
+                agent_id in any::<usize>(),
+            ) {
+                let msg = build(
+                    &Some(users.clone()),
+                    &Some(groups.clone()),
+                    &Some(tags.clone()),
+                    Some(agent_id),
+                )
+                .unwrap();
+                prop_assert_eq!(&msg["touser"], &json!(users.join("|")));
+                prop_assert_eq!(&msg["toparty"], &json!(groups.join("|")));
+                prop_assert_eq!(&msg["totag"], &json!(tags.join("|")));
+                prop_assert_eq!(&msg["agentid"], &json!(agent_id));
+                prop_assert_eq!(&msg["msgtype"], "text");
+                prop_assert_eq!(&msg["text"]["content"], "hello");
+            }
+
+            #[test]
+            fn test_build_never_panics(
+                users in prop::option::of(ids()),
+                groups in prop::option::of(ids()),
+                tags in prop::option::of(ids()),
+                agent_id in prop::option::of(any::<usize>()),
+            ) {
+                let result = build(&users, &groups, &tags, agent_id);
+                let has_recipient = users.is_some() || groups.is_some() || tags.is_some();
+                prop_assert_eq!(result.is_ok(), has_recipient && agent_id.is_some());
+            }
+
+            #[test]
+            fn test_round_trip(
+                users in ids(),
+                agent_id in any::<usize>(),
+            ) {
+                let msg = build(&Some(users), &None, &None, Some(agent_id)).unwrap();
+                let text = serde_json::to_string(&msg).unwrap();
+                let parsed: Value = serde_json::from_str(&text).unwrap();
+                prop_assert_eq!(serde_json::to_value(&parsed).unwrap(), msg);
+            }
+        }
+    }
+
+    #[test]
+    fn test_news_article_miniprogram() {
+        let article = NewsArticle::new("值班表".to_string(), "https://example.com".to_string())
+            .with_miniprogram("wx123456", Some("pages/index?x=1"));
+        let value = serde_json::to_value(&article).unwrap();
+        assert_eq!(value["url"], "https://example.com");
+        assert_eq!(value["appid"], "wx123456");
+        assert_eq!(value["pagepath"], "pages/index?x=1");
+        assert_eq!(value["title"], "值班表");
+
+        let article = NewsArticle::new("值班表".to_string(), "https://example.com".to_string());
+        let value = serde_json::to_value(&article).unwrap();
+        assert!(value.get("appid").is_none());
+        assert!(value.get("pagepath").is_none());
+    }
+
+    #[test]
+    fn test_oa_msg() {
+        let builder = MessageBuilder::default()
+            .to_users(vec!["robin"])
+            .from_agent(1);
+        let oa = || {
+            OAMsg::new(
+                OAMsgHead::new("BLUE".to_string(), "请假审批".to_string()),
+                OAMsgBody::new("申请人：张三\n天数：1天".to_string()),
+            )
+        };
+
+        let msg = builder.build(oa()).unwrap();
+        assert_eq!(msg["msgtype"], "oa");
+        assert_eq!(
+            msg["oa"],
+            json!({
+                "head": {"bgcolor": "BLUE", "text": "请假审批"},
+                "body": {"content": "申请人：张三\n天数：1天"}
+            })
+        );
+
+        let msg = builder
+            .build(
+                oa().with_url("https://example.com/approval/42".to_string())
+                    .with_pc_url("https://example.com/pc/approval/42".to_string()),
+            )
+            .unwrap();
+        assert_eq!(msg["oa"]["url"], "https://example.com/approval/42");
+        assert_eq!(msg["oa"]["pc_url"], "https://example.com/pc/approval/42");
+
+        let purple = OAMsg::new(
+            OAMsgHead::new("PURPLE".to_string(), "请假审批".to_string()),
+            OAMsgBody::new(String::new()),
+        );
+        assert!(matches!(
+            builder.build(purple),
+            Err(WecomError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_location_msg() {
+        let builder = MessageBuilder::default()
+            .to_users(vec!["robin"])
+            .from_agent(1);
+        let msg = builder
+            .build(LocationMsg::new(
+                23.134521,
+                113.358803,
+                15,
+                "广州市天河区".to_string(),
+            ))
+            .unwrap();
+        assert_eq!(msg["msgtype"], "location");
+        assert_eq!(
+            msg["location"],
+            json!({"latitude": 23.134521, "longitude": 113.358803, "scale": 15, "label": "广州市天河区"})
+        );
+
+        // 边界值有效
+        for (latitude, longitude, scale) in [(90.0, 180.0, 3), (-90.0, -180.0, 15), (0.0, 0.0, 10)]
+        {
+            assert!(LocationMsg::new(latitude, longitude, scale, String::new())
+                .validate()
+                .is_ok());
+        }
+        for (latitude, longitude, scale) in [
+            (90.000001, 0.0, 10),
+            (-90.1, 0.0, 10),
+            (0.0, 180.1, 10),
+            (0.0, -180.000001, 10),
+            (f64::NAN, 0.0, 10),
+            (0.0, 0.0, 2),
+            (0.0, 0.0, 16),
+        ] {
+            assert!(matches!(
+                builder.build(LocationMsg::new(latitude, longitude, scale, String::new())),
+                Err(WecomError::InvalidArgument(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_update_taskcard_builder() {
+        let request = UpdateTaskCardRequest::builder()
+            .to_users(vec!["robin"])
+            .from_agent(1)
+            .task_id("task-42")
+            .build("approve")
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "userids": ["robin"],
+                "agentid": 1,
+                "task_id": "task-42",
+                "clicked_key": "approve"
+            })
+        );
+
+        let builder = || {
+            UpdateTaskCardRequest::builder()
+                .from_agent(1)
+                .task_id("task-42")
+        };
+        assert!(matches!(
+            builder().build("approve"),
+            Err(WecomError::Build(_))
+        ));
+        let users = crate::user_ids(1001);
+        assert!(matches!(
+            builder()
+                .to_users(users.iter().map(String::as_str).collect())
+                .build("approve"),
+            Err(WecomError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_update_template_card_builder() {
+        let button = || CardUpdate::Button {
+            replace_name: "已同意".to_string(),
+        };
+        let request = UpdateTemplateCardRequest::builder()
+            .response_code("CODE")
+            .to_users(vec!["robin", "tom"])
+            .from_agent(1)
+            .build(button())
+            .unwrap();
+        assert_eq!(
+            request.to_json(),
+            json!({
+                "userids": ["robin", "tom"],
+                "agentid": 1,
+                "response_code": "CODE",
+                "button": {"replace_name": "已同意"}
+            })
+        );
+
+        let builder = || {
+            UpdateTemplateCardRequest::builder()
+                .response_code("CODE")
+                .from_agent(1)
+        };
+        assert!(matches!(
+            builder().build(button()),
+            Err(WecomError::Build(_))
+        ));
+        assert!(matches!(
+            builder().to_all().to_users(vec!["robin"]).build(button()),
+            Err(WecomError::Build(_))
+        ));
+        let users = crate::user_ids(101);
+        assert!(matches!(
+            builder()
+                .to_users(users.iter().map(String::as_str).collect())
+                .build(button()),
+            Err(WecomError::InvalidArgument(_))
+        ));
+        assert!(builder()
+            .to_users(users[..100].iter().map(String::as_str).collect())
+            .build(button())
+            .is_ok());
+    }
+
     #[test]
     fn test_builder() {
         let content = Text::new("hello text!".to_string());
@@ -308,9 +1418,9 @@ mod test {
             .to_groups(vec!["a", "b", "c"])
             .to_tags(vec!["x", "y", "z"])
             .from_agent(1)
-            .with_safe(1)
-            .with_enable_id_trans(1)
-            .with_enable_duplicate_check(1)
+            .with_safe(true)
+            .with_enable_id_trans(true)
+            .with_enable_duplicate_check(true)
             .with_duplicate_check_interval(800)
             .build(content)
             .expect("Massage should be built");
@@ -330,4 +1440,322 @@ mod test {
         });
         assert_eq!(msg, serde_json::to_value(raw).unwrap());
     }
+
+    #[test]
+    fn test_build_json_string_and_bytes() {
+        let builder = MessageBuilder::default()
+            .to_users(vec!["robin"])
+            .from_agent(1);
+        let expected = builder.build(Text::new("hello".to_string())).unwrap();
+
+        let s = builder
+            .build_json_string(Text::new("hello".to_string()))
+            .unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&s).unwrap(), expected);
+
+        let bytes = builder
+            .build_json_bytes(Text::new("hello".to_string()))
+            .unwrap();
+        assert_eq!(serde_json::from_slice::<Value>(&bytes).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_build_owned() {
+        let builder = MessageBuilder::default()
+            .to_users(vec!["robin"])
+            .from_agent(1);
+        let expected = builder.build(Text::new("hello".to_string())).unwrap();
+        let msg = MessageBuilder::default()
+            .to_users(vec!["robin"])
+            .from_agent(1)
+            .build_owned(Text::new("hello".to_string()))
+            .unwrap();
+        assert_eq!(msg, expected);
+    }
+
+    #[test]
+    fn test_news() {
+        let article = NewsArticle::new(
+            "中秋节礼品领取".to_string(),
+            "https://work.weixin.qq.com".to_string(),
+        )
+        .with_description("今年中秋节公司有豪礼相送".to_string());
+        let msg = MessageBuilder::default()
+            .to_users(vec!["robin"])
+            .from_agent(1)
+            .build(NewsMsg::new(vec![article]).unwrap())
+            .unwrap();
+        assert_eq!(msg["msgtype"], "news");
+        assert_eq!(
+            msg["news"],
+            json!({
+                "articles": [{
+                    "title": "中秋节礼品领取",
+                    "description": "今年中秋节公司有豪礼相送",
+                    "url": "https://work.weixin.qq.com"
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_news_validation() {
+        let article = |url: &str| NewsArticle::new("title".to_string(), url.to_string());
+        let https = || article("https://work.weixin.qq.com");
+
+        assert!(matches!(
+            NewsMsg::new(vec![]),
+            Err(WecomError::InvalidArgument(_))
+        ));
+        assert!(NewsMsg::new(vec![https(); 8]).is_ok());
+        assert!(matches!(
+            NewsMsg::new(vec![https(); 9]),
+            Err(WecomError::InvalidArgument(_))
+        ));
+        for url in ["http://work.weixin.qq.com", "work.weixin.qq.com", ""] {
+            assert!(matches!(
+                NewsMsg::new(vec![article(url)]),
+                Err(WecomError::InvalidUrl(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_news_picurl() {
+        let article = NewsArticle::new(
+            "title".to_string(),
+            "https://work.weixin.qq.com".to_string(),
+        );
+        let value = serde_json::to_value(NewsMsg::new(vec![article.clone()]).unwrap()).unwrap();
+        assert!(value["articles"][0].get("picurl").is_none());
+
+        let picurl =
+            "https://res.mail.qq.com/node/ww/wwopenmng/images/independent/doc/test_pic_msg1.png";
+        let news = NewsMsg::new(vec![article.clone().with_picurl(picurl.to_string())]).unwrap();
+        assert_eq!(
+            serde_json::to_value(news).unwrap()["articles"][0]["picurl"],
+            picurl
+        );
+
+        for picurl in ["not-a-url", "http://p.qpic.cn/pic_wework/0"] {
+            assert!(matches!(
+                NewsMsg::new(vec![article.clone().with_picurl(picurl.to_string())]),
+                Err(WecomError::InvalidUrl(url)) if url == picurl
+            ));
+        }
+    }
+
+    #[test]
+    fn test_news_length_limits() {
+        let article = |title: String, description: String| {
+            NewsArticle::new(title, "https://work.weixin.qq.com".to_string())
+                .with_description(description)
+        };
+        let news = |title: &str, n, description: &str, m| {
+            NewsMsg::new(vec![article(title.repeat(n), description.repeat(m))])
+        };
+
+        assert!(news("a", 128, "b", 512).is_ok());
+        // 中文字符按1个字符计
+        assert!(news("中", 128, "文", 512).is_ok());
+        assert!(matches!(
+            news("中", 129, "文", 1),
+            Err(WecomError::ContentTooLong {
+                field: "news.title",
+                actual: 129,
+                limit: 128,
+                unit: LengthUnit::Chars
+            })
+        ));
+        assert!(matches!(
+            news("a", 1, "b", 513),
+            Err(WecomError::ContentTooLong {
+                field: "news.description",
+                actual: 513,
+                limit: 512,
+                unit: LengthUnit::Chars
+            })
+        ));
+    }
+
+    #[test]
+    fn test_text_length_limit() {
+        let builder = MessageBuilder::default()
+            .to_users(vec!["robin"])
+            .from_agent(1);
+        assert!(builder.build(Text::new("a".repeat(2048))).is_ok());
+        assert!(matches!(
+            builder.build(Text::new("a".repeat(2049))),
+            Err(WecomError::ContentTooLong {
+                field: "text.content",
+                actual: 2049,
+                limit: 2048,
+                unit: LengthUnit::Bytes
+            })
+        ));
+        // 中文字符占3个字节
+        assert!(matches!(
+            builder.build(Text::new("中".repeat(683))),
+            Err(WecomError::ContentTooLong { actual: 2049, .. })
+        ));
+    }
+
+    #[test]
+    fn test_markdown_length_limit() {
+        let builder = MessageBuilder::default()
+            .to_users(vec!["robin"])
+            .from_agent(1);
+        let msg = builder.build(MarkDownMsg::new("a".repeat(4096))).unwrap();
+        assert_eq!(msg["markdown"]["content"].as_str().unwrap().len(), 4096);
+        let e = builder
+            .build(MarkDownMsg::new("a".repeat(4097)))
+            .unwrap_err();
+        assert!(matches!(
+            e,
+            WecomError::ContentTooLong {
+                field: "markdown.content",
+                actual: 4097,
+                limit: 4096,
+                unit: LengthUnit::Bytes
+            }
+        ));
+        assert_eq!(
+            e.to_string(),
+            "markdown.content超出长度上限：4097个字节，上限4096个字节"
+        );
+
+        // 按字节而非字符计：1366个中文字符共4098个字节
+        assert!(builder.build(MarkDownMsg::new("中".repeat(1365))).is_ok());
+        assert!(matches!(
+            builder.build(MarkDownMsg::new("中".repeat(1366))),
+            Err(WecomError::ContentTooLong { actual: 4098, .. })
+        ));
+    }
+
+    #[test]
+    fn test_textcard_btntxt_limit() {
+        let builder = MessageBuilder::default()
+            .to_users(vec!["robin"])
+            .from_agent(1);
+        let card = |btntxt: Option<&str>| {
+            let card = TextCardMsg::new(
+                "领奖通知".to_string(),
+                "恭喜中奖".to_string(),
+                "https://example.com".to_string(),
+            );
+            builder.build(match btntxt {
+                Some(btntxt) => card.with_btntxt(btntxt.to_string()),
+                None => card,
+            })
+        };
+
+        assert_eq!(
+            card(Some("查看详情")).unwrap()["textcard"]["btntxt"],
+            "查看详情"
+        );
+        assert!(card(None).is_ok());
+        assert!(matches!(
+            card(Some("查看全部详情")),
+            Err(WecomError::ContentTooLong {
+                field: "textcard.btntxt",
+                actual: 6,
+                limit: 4,
+                unit: LengthUnit::Chars
+            })
+        ));
+        assert!(matches!(
+            card(Some("more!")),
+            Err(WecomError::ContentTooLong { actual: 5, .. })
+        ));
+    }
+
+    #[test]
+    fn test_textcard_length_limits() {
+        let builder = MessageBuilder::default()
+            .to_users(vec!["robin"])
+            .from_agent(1);
+        let card = |title: String, description: String, url: String| {
+            builder.build(TextCardMsg::new(title, description, url))
+        };
+        let url = |n: usize| format!("https://{}", "a".repeat(n - 8));
+
+        let msg = card("中".repeat(128), "文".repeat(512), url(512)).unwrap();
+        assert_eq!(msg["msgtype"], "textcard");
+        assert!(msg["textcard"].get("btntxt").is_none());
+
+        assert!(matches!(
+            card("中".repeat(129), "b".to_string(), url(20)),
+            Err(WecomError::ContentTooLong {
+                field: "textcard.title",
+                actual: 129,
+                limit: 128,
+                unit: LengthUnit::Chars
+            })
+        ));
+        assert!(matches!(
+            card("a".to_string(), "文".repeat(513), url(20)),
+            Err(WecomError::ContentTooLong {
+                field: "textcard.description",
+                actual: 513,
+                limit: 512,
+                unit: LengthUnit::Chars
+            })
+        ));
+        assert!(matches!(
+            card("a".to_string(), "b".to_string(), url(513)),
+            Err(WecomError::ContentTooLong {
+                field: "textcard.url",
+                actual: 513,
+                limit: 512,
+                unit: LengthUnit::Bytes
+            })
+        ));
+    }
+
+    #[test]
+    fn test_markdown_builder() {
+        let msg = MarkdownBuilder::new()
+            .bold("发布完成")
+            .newline()
+            .text("状态：")
+            .color("成功", MarkdownColor::Info)
+            .text("，")
+            .color("2个告警", MarkdownColor::Warning)
+            .newline()
+            .italic("耗时")
+            .text(" ")
+            .color("3分钟", MarkdownColor::Comment)
+            .newline()
+            .link("查看详情", "https://work.weixin.qq.com")
+            .code_block("rust", "fn main() {}\n")
+            .build();
+        assert_eq!(
+            msg,
+            MarkDownMsg::new(
+                "**发布完成**\n\
+                 状态：<font color=\"info\">成功</font>，<font color=\"warning\">2个告警</font>\n\
+                 *耗时* <font color=\"comment\">3分钟</font>\n\
+                 [查看详情](https://work.weixin.qq.com)\n\
+                 ```rust\nfn main() {}\n```\n"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            MarkdownBuilder::new().build(),
+            MarkDownMsg::new(String::new())
+        );
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        let no_recipient = MessageBuilder::default()
+            .from_agent(1)
+            .build(Text::new("hello".to_string()));
+        assert!(matches!(no_recipient, Err(WecomError::Build(_))));
+
+        let no_agent = MessageBuilder::default()
+            .to_users(vec!["robin"])
+            .build(Text::new("hello".to_string()));
+        assert!(matches!(no_agent, Err(WecomError::Build(_))));
+    }
 }
@@ -0,0 +1,168 @@
+use crate::{MsgSendResponse, WecomError};
+use opentelemetry::global::{BoxedSpan, BoxedTracer};
+use opentelemetry::trace::{Span, Status, Tracer};
+use opentelemetry::KeyValue;
+use serde::Serialize;
+
+// 为一次消息发送创建span，消息中的agentid与msgtype记为属性
+pub(crate) fn send_span<T: Serialize>(tracer: &BoxedTracer, corp_id: &str, msg: &T) -> BoxedSpan {
+    let mut span = tracer.start("wecom.send");
+    span.set_attribute(KeyValue::new("wecom.corp_id", corp_id.to_owned()));
+    if let Ok(value) = serde_json::to_value(msg) {
+        if let Some(agent_id) = value.get("agentid").and_then(|v| v.as_i64()) {
+            span.set_attribute(KeyValue::new("wecom.agent_id", agent_id));
+        }
+        if let Some(msg_type) = value.get("msgtype").and_then(|v| v.as_str()) {
+            span.set_attribute(KeyValue::new("wecom.msg_type", msg_type.to_owned()));
+        }
+    }
+    span
+}
+
+pub(crate) fn finish_send_span(
+    span: &mut BoxedSpan,
+    result: &Result<MsgSendResponse, WecomError>,
+    attempt: u32,
+) {
+    span.set_attribute(KeyValue::new("wecom.attempt", attempt as i64));
+    match result {
+        Ok(response) => {
+            span.set_attribute(KeyValue::new("wecom.errcode", response.error_code()));
+            if response.is_error() {
+                span.set_status(Status::error(response.error_msg().to_owned()));
+            }
+        }
+        Err(e) => record_error(span, e),
+    }
+    span.end();
+}
+
+// 为一次access token更新创建span
+pub(crate) fn token_span(tracer: &BoxedTracer, corp_id: &str) -> BoxedSpan {
+    let mut span = tracer.start("wecom.update_token");
+    span.set_attribute(KeyValue::new("wecom.corp_id", corp_id.to_owned()));
+    span
+}
+
+pub(crate) fn finish_token_span(span: &mut BoxedSpan, result: &Result<(), WecomError>) {
+    match result {
+        Ok(()) => span.set_attribute(KeyValue::new("wecom.errcode", 0)),
+        Err(e) => record_error(span, e),
+    }
+    span.end();
+}
+
+fn record_error(span: &mut BoxedSpan, e: &WecomError) {
+    if let WecomError::Api { .. } | WecomError::IpNotAllowed { .. } = e {
+        span.set_attribute(KeyValue::new("wecom.errcode", e.code()));
+    }
+    span.record_error(e);
+    span.set_status(Status::error(e.to_string()));
+}
+
+#[cfg(test)]
+mod test {
+    use crate::message::{MessageBuilder, Text};
+    use crate::WecomAgentBuilder;
+    use opentelemetry::global::BoxedTracer;
+    use opentelemetry::trace::{Status, TracerProvider};
+    use opentelemetry::{KeyValue, Value};
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider, SpanData};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn attribute<'a>(span: &'a SpanData, key: &str) -> Option<&'a Value> {
+        span.attributes
+            .iter()
+            .find(|kv: &&KeyValue| kv.key.as_str() == key)
+            .map(|kv| &kv.value)
+    }
+
+    #[tokio::test]
+    async fn test_spans_exported() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cgi-bin/gettoken"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "errcode": 0, "errmsg": "ok", "access_token": "token", "expires_in": 7200
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/cgi-bin/message/send"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "errcode": 0, "errmsg": "ok", "msgid": "MSGID"
+            })))
+            .mount(&server)
+            .await;
+
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let agent = WecomAgentBuilder::new("corp", "secret")
+            .base_url(&server.uri())
+            .with_tracer(BoxedTracer::new(Box::new(provider.tracer("test"))))
+            .build()
+            .unwrap();
+
+        let msg = MessageBuilder::default()
+            .to_users(vec!["robin"])
+            .from_agent(42)
+            .build(Text::new("hello".to_string()))
+            .unwrap();
+        agent.send(msg).await.unwrap();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let token = spans
+            .iter()
+            .find(|s| s.name == "wecom.update_token")
+            .unwrap();
+        assert_eq!(
+            attribute(token, "wecom.corp_id"),
+            Some(&Value::from("corp"))
+        );
+        let send = spans.iter().find(|s| s.name == "wecom.send").unwrap();
+        assert_eq!(attribute(send, "wecom.corp_id"), Some(&Value::from("corp")));
+        assert_eq!(attribute(send, "wecom.agent_id"), Some(&Value::I64(42)));
+        assert_eq!(
+            attribute(send, "wecom.msg_type"),
+            Some(&Value::from("text"))
+        );
+        assert_eq!(attribute(send, "wecom.errcode"), Some(&Value::I64(0)));
+        assert_eq!(attribute(send, "wecom.attempt"), Some(&Value::I64(1)));
+        assert_eq!(send.status, Status::Unset);
+    }
+
+    #[tokio::test]
+    async fn test_span_records_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cgi-bin/gettoken"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "errcode": 40001, "errmsg": "invalid credential"
+            })))
+            .mount(&server)
+            .await;
+
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let agent = WecomAgentBuilder::new("corp", "secret")
+            .base_url(&server.uri())
+            .with_tracer(BoxedTracer::new(Box::new(provider.tracer("test"))))
+            .build()
+            .unwrap();
+
+        assert!(agent.update_token(0).await.is_err());
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let token = spans
+            .iter()
+            .find(|s| s.name == "wecom.update_token")
+            .unwrap();
+        assert_eq!(attribute(token, "wecom.errcode"), Some(&Value::I64(40001)));
+        assert!(matches!(token.status, Status::Error { .. }));
+    }
+}
@@ -0,0 +1,77 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// HTTP连接池的瞬时负载
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ConnectionPoolMetrics {
+    /// 正在进行的请求数
+    pub active_requests: u32,
+    /// 因并发上限而等待中的请求数
+    pub queued_requests: u32,
+}
+
+// 统计进行中与排队中的请求。设定并发上限时，超出上限的请求将排队等待。
+#[derive(Debug)]
+pub(crate) struct RequestGauge {
+    active: AtomicU32,
+    queued: AtomicU32,
+    limit: Option<Semaphore>,
+}
+
+impl RequestGauge {
+    pub fn new(max_concurrent: Option<usize>) -> Self {
+        Self {
+            active: AtomicU32::new(0),
+            queued: AtomicU32::new(0),
+            limit: max_concurrent.map(Semaphore::new),
+        }
+    }
+
+    // 登记一个请求。返回的守卫在请求结束（被丢弃）时注销该请求。
+    pub async fn enter(&self) -> InFlight<'_> {
+        let permit = match &self.limit {
+            Some(semaphore) => {
+                let _queued = Counted::new(&self.queued);
+                Some(
+                    semaphore
+                        .acquire()
+                        .await
+                        .expect("Semaphore should never be closed"),
+                )
+            }
+            None => None,
+        };
+        InFlight {
+            _active: Counted::new(&self.active),
+            _permit: permit,
+        }
+    }
+
+    pub fn metrics(&self) -> ConnectionPoolMetrics {
+        ConnectionPoolMetrics {
+            active_requests: self.active.load(Ordering::SeqCst),
+            queued_requests: self.queued.load(Ordering::SeqCst),
+        }
+    }
+}
+
+pub(crate) struct InFlight<'a> {
+    _active: Counted<'a>,
+    _permit: Option<SemaphorePermit<'a>>,
+}
+
+// 存活期间计数加一
+struct Counted<'a>(&'a AtomicU32);
+
+impl<'a> Counted<'a> {
+    fn new(counter: &'a AtomicU32) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter)
+    }
+}
+
+impl Drop for Counted<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
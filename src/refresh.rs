@@ -0,0 +1,243 @@
+use crate::WecomAgent;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+// 更新失败后的最短等待时长，避免配置为0时反复请求
+const MIN_FAILURE_BACKOFF: Duration = Duration::from_millis(100);
+
+/// access token后台自动更新的配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoRefreshConfig {
+    /// 在凭据过期前多少秒更新，默认300秒。超过凭据有效期的一半时按一半计。
+    pub refresh_before_expiry_secs: u64,
+    /// 更新失败后的首次等待时长，默认5秒。连续失败时等待时长逐次翻倍，最短100毫秒。
+    pub min_failure_backoff: Duration,
+    /// 更新失败后的最长等待时长，默认5分钟。
+    pub max_failure_backoff: Duration,
+    /// 两次检查凭据的最长间隔，默认60秒。时钟被调整（如系统休眠后或测试中的`MockClock`）时，
+    /// 最迟在此间隔后按新的时刻判断是否需要更新。
+    pub max_check_interval: Duration,
+}
+
+impl Default for AutoRefreshConfig {
+    fn default() -> Self {
+        Self {
+            refresh_before_expiry_secs: 300,
+            min_failure_backoff: Duration::from_secs(5),
+            max_failure_backoff: Duration::from_secs(5 * 60),
+            max_check_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+impl AutoRefreshConfig {
+    // 连续失败后的下一次等待时长
+    fn next_backoff(&self, current: Option<Duration>) -> Duration {
+        match current {
+            Some(d) => d.saturating_mul(2),
+            None => self.min_failure_backoff,
+        }
+        .min(self.max_failure_backoff)
+        .max(MIN_FAILURE_BACKOFF)
+    }
+}
+
+impl WecomAgent {
+    /// 启动后台任务，在access token过期前自动更新。
+    ///
+    /// 每次唤醒时重新检查凭据，已被其他调用更新时不再重复更新。
+    /// 更新失败时按指数退避重试，等待时长介于`min_failure_backoff`与`max_failure_backoff`之间。
+    /// Agent被释放后，任务在下次唤醒时结束；也可通过返回的`JoinHandle`提前终止。
+    pub fn start_auto_refresh(self: &Arc<Self>, config: AutoRefreshConfig) -> JoinHandle<()> {
+        let agent = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut failure_backoff = None;
+            loop {
+                let Some(agent) = agent.upgrade() else {
+                    return;
+                };
+                let wait = agent
+                    .refresh_due_in(config.refresh_before_expiry_secs)
+                    .await;
+                if !wait.is_zero() {
+                    drop(agent);
+                    tokio::time::sleep(wait.min(config.max_check_interval)).await;
+                    continue;
+                }
+
+                match agent.update_token(0).await {
+                    Ok(()) => {
+                        info!("Token updated");
+                        failure_backoff = None;
+                    }
+                    Err(e) => {
+                        let backoff = config.next_backoff(failure_backoff);
+                        warn!("Auto refresh failed: {e}. Retrying in {:?}...", backoff);
+                        failure_backoff = Some(backoff);
+                        drop(agent);
+                        tokio::time::sleep(backoff).await;
+                    }
+                }
+            }
+        })
+    }
+
+    // 距离下次需要更新access token的时长。提前量不超过有效期的一半，以免刚更新即再次更新。
+    async fn refresh_due_in(&self, refresh_before_expiry_secs: u64) -> Duration {
+        let access_token = self.access_token.read().await;
+        if access_token.needs_refresh() {
+            return Duration::ZERO;
+        }
+        let before = Duration::from_secs(refresh_before_expiry_secs).min(access_token.lifetime / 2);
+        access_token.remaining().saturating_sub(before)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MockClock;
+    use std::time::Instant;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_next_backoff() {
+        let config = AutoRefreshConfig {
+            refresh_before_expiry_secs: 300,
+            min_failure_backoff: Duration::from_secs(5),
+            max_failure_backoff: Duration::from_secs(30),
+            ..Default::default()
+        };
+        let mut backoff = None;
+        let mut seq = vec![];
+        for _ in 0..5 {
+            let next = config.next_backoff(backoff);
+            seq.push(next.as_secs());
+            backoff = Some(next);
+        }
+        assert_eq!(seq, vec![5, 10, 20, 30, 30]);
+
+        // 等待时长为0时按最短等待时长计，不会反复请求
+        let config = AutoRefreshConfig {
+            min_failure_backoff: Duration::ZERO,
+            max_failure_backoff: Duration::ZERO,
+            ..Default::default()
+        };
+        assert_eq!(config.next_backoff(None), MIN_FAILURE_BACKOFF);
+        assert_eq!(
+            config.next_backoff(Some(Duration::ZERO)),
+            MIN_FAILURE_BACKOFF
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auto_refresh_backoff() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cgi-bin/gettoken"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"errcode": -1, "errmsg": "system busy"})),
+            )
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cgi-bin/gettoken"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "errcode": 0, "errmsg": "ok", "access_token": "token", "expires_in": 7200
+            })))
+            .mount(&server)
+            .await;
+
+        let clock = MockClock::default();
+        let agent = Arc::new(
+            WecomAgent::builder("corp", "secret")
+                .base_url(&server.uri())
+                .with_clock(Arc::new(clock.clone()))
+                .build()
+                .unwrap(),
+        );
+        let config = AutoRefreshConfig {
+            refresh_before_expiry_secs: 300,
+            min_failure_backoff: Duration::from_millis(100),
+            max_failure_backoff: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let handle = agent.start_auto_refresh(config);
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !agent.access_token.read().await.is_initialized() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("token should be refreshed");
+        // 两次失败分别等待100与200毫秒
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+
+        // 依据时钟，下次更新在过期前300秒
+        assert_eq!(
+            agent.refresh_due_in(300).await,
+            Duration::from_secs(7200 - 300)
+        );
+        clock.advance(Duration::from_secs(7000));
+        assert_eq!(agent.refresh_due_in(300).await, Duration::ZERO);
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_auto_refresh_follows_clock() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cgi-bin/gettoken"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "errcode": 0, "errmsg": "ok", "access_token": "token", "expires_in": 7200
+            })))
+            .mount(&server)
+            .await;
+        let clock = MockClock::default();
+        let agent = Arc::new(
+            WecomAgent::builder("corp", "secret")
+                .base_url(&server.uri())
+                .with_clock(Arc::new(clock.clone()))
+                .build()
+                .unwrap(),
+        );
+        let handle = agent.start_auto_refresh(AutoRefreshConfig {
+            max_check_interval: Duration::from_millis(10),
+            ..Default::default()
+        });
+        let wait_for_requests = |n: usize| {
+            let server = &server;
+            async move {
+                tokio::time::timeout(Duration::from_secs(5), async {
+                    while server.received_requests().await.unwrap().len() < n {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                    }
+                })
+                .await
+                .expect("token should be refreshed");
+            }
+        };
+        wait_for_requests(1).await;
+
+        // 凭据未到更新时刻，不重复更新
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+
+        // 时钟前进到过期前300秒，下一次检查即更新
+        clock.advance(Duration::from_secs(7200 - 300));
+        wait_for_requests(2).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+
+        handle.abort();
+    }
+}
@@ -0,0 +1,259 @@
+use crate::{MsgSendResponse, WecomError};
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+/// 批量发送的聚合结果
+///
+/// 以消息在批次中的索引记录每条消息的结果。errcode非0的响应记为失败，并转换为`WecomError`。
+#[derive(Debug, Default)]
+pub struct SendReport {
+    results: BTreeMap<usize, Result<MsgSendResponse, WecomError>>,
+    invalid_users: BTreeSet<String>,
+    unlicensed_users: BTreeSet<String>,
+    elapsed: Duration,
+}
+
+impl SendReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录第`index`条消息的发送结果。同一索引重复记录时以后者为准。
+    pub fn record(&mut self, index: usize, result: Result<MsgSendResponse, WecomError>) {
+        let result = match result {
+            Ok(response) => {
+                self.invalid_users.extend(response.invalid_users());
+                self.unlicensed_users.extend(response.unlicensed_users());
+                if response.is_error() {
+                    Err(WecomError::api(
+                        response.error_code(),
+                        response.error_msg().to_owned(),
+                    ))
+                } else {
+                    Ok(response)
+                }
+            }
+            Err(e) => {
+                if let WecomError::AllRecipientsInvalid { users, .. } = &e {
+                    self.invalid_users.extend(users.iter().cloned());
+                }
+                if let WecomError::UnlicensedRecipients { users, .. } = &e {
+                    self.unlicensed_users.extend(users.iter().cloned());
+                }
+                Err(e)
+            }
+        };
+        self.results.insert(index, result);
+    }
+
+    /// 设定总耗时
+    pub fn set_elapsed(&mut self, elapsed: Duration) {
+        self.elapsed = elapsed;
+    }
+
+    /// 合并另一批次的结果。`other`中的索引顺延在本批次之后，耗时累加。
+    pub fn merge(&mut self, other: SendReport) {
+        let offset = self.results.keys().next_back().map_or(0, |i| i + 1);
+        self.results.extend(
+            other
+                .results
+                .into_iter()
+                .map(|(index, result)| (index + offset, result)),
+        );
+        self.invalid_users.extend(other.invalid_users);
+        self.unlicensed_users.extend(other.unlicensed_users);
+        self.elapsed += other.elapsed;
+    }
+
+    pub fn total(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn succeeded(&self) -> usize {
+        self.results.values().filter(|r| r.is_ok()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.total() - self.succeeded()
+    }
+
+    /// 是否全部发送成功
+    pub fn is_all_ok(&self) -> bool {
+        self.results.values().all(|r| r.is_ok())
+    }
+
+    /// 失败消息的索引，升序排列
+    pub fn failed_indices(&self) -> Vec<usize> {
+        self.results
+            .iter()
+            .filter(|(_, r)| r.is_err())
+            .map(|(index, _)| *index)
+            .collect()
+    }
+
+    /// 每条消息的结果，以索引为键
+    pub fn results(&self) -> &BTreeMap<usize, Result<MsgSendResponse, WecomError>> {
+        &self.results
+    }
+
+    /// 全部消息中无效或无权限的成员，已去重
+    pub fn invalid_users(&self) -> &BTreeSet<String> {
+        &self.invalid_users
+    }
+
+    /// 全部消息中未激活接口许可的成员，已去重
+    pub fn unlicensed_users(&self) -> &BTreeSet<String> {
+        &self.unlicensed_users
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+// 序列化时，失败项输出错误码与错误描述
+#[derive(Serialize)]
+struct ReportView<'a> {
+    total: usize,
+    succeeded: usize,
+    failed: usize,
+    elapsed_ms: u128,
+    invalid_users: &'a BTreeSet<String>,
+    unlicensed_users: &'a BTreeSet<String>,
+    results: BTreeMap<usize, ResultView<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ResultView<'a> {
+    Ok(&'a MsgSendResponse),
+    Error { code: i64, message: String },
+}
+
+impl Serialize for SendReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ReportView {
+            total: self.total(),
+            succeeded: self.succeeded(),
+            failed: self.failed(),
+            elapsed_ms: self.elapsed.as_millis(),
+            invalid_users: &self.invalid_users,
+            unlicensed_users: &self.unlicensed_users,
+            results: self
+                .results
+                .iter()
+                .map(|(index, result)| {
+                    let view = match result {
+                        Ok(response) => ResultView::Ok(response),
+                        Err(e) => ResultView::Error {
+                            code: e.code(),
+                            message: e.to_string(),
+                        },
+                    };
+                    (*index, view)
+                })
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn response(value: serde_json::Value) -> MsgSendResponse {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn sample() -> SendReport {
+        let mut report = SendReport::new();
+        report.record(
+            0,
+            Ok(response(
+                json!({"errcode": 0, "errmsg": "ok", "invaliduser": "tom", "msgid": "A"}),
+            )),
+        );
+        report.record(
+            1,
+            Ok(response(
+                json!({"errcode": 0, "errmsg": "ok", "unlicenseduser": "amy|tom", "msgid": "B"}),
+            )),
+        );
+        report.record(
+            2,
+            Err(WecomError::AllRecipientsInvalid {
+                users: vec!["tom".to_string(), "bob".to_string()],
+                parties: vec![],
+                tags: vec![],
+                context: None,
+            }),
+        );
+        report.record(
+            3,
+            Ok(response(
+                json!({"errcode": 0, "errmsg": "ok", "msgid": "C"}),
+            )),
+        );
+        report.record(
+            4,
+            Ok(response(
+                json!({"errcode": 60011, "errmsg": "no privilege to access/modify contact/party/agent"}),
+            )),
+        );
+        report.set_elapsed(Duration::from_millis(1500));
+        report
+    }
+
+    #[test]
+    fn test_statistics() {
+        let report = sample();
+        assert_eq!(report.total(), 5);
+        assert_eq!(report.succeeded(), 3);
+        assert_eq!(report.failed(), 2);
+        assert!(!report.is_all_ok());
+        assert_eq!(report.failed_indices(), vec![2, 4]);
+        assert!(matches!(
+            report.results()[&2],
+            Err(WecomError::AllRecipientsInvalid { .. })
+        ));
+        assert!(report.results()[&4]
+            .as_ref()
+            .is_err_and(|e| e.is_permission_denied()));
+        assert_eq!(
+            report.invalid_users().iter().collect::<Vec<_>>(),
+            vec!["bob", "tom"]
+        );
+        assert_eq!(
+            report.unlicensed_users().iter().collect::<Vec<_>>(),
+            vec!["amy", "tom"]
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut report = sample();
+        let mut other = SendReport::new();
+        other.record(0, Err(WecomError::Build("收件人不可为空".to_string())));
+        other.set_elapsed(Duration::from_millis(500));
+        report.merge(other);
+
+        assert_eq!(report.total(), 6);
+        assert_eq!(report.failed_indices(), vec![2, 4, 5]);
+        assert_eq!(report.elapsed(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_serialize() {
+        let value = serde_json::to_value(sample()).unwrap();
+        assert_eq!(value["total"], 5);
+        assert_eq!(value["succeeded"], 3);
+        assert_eq!(value["failed"], 2);
+        assert_eq!(value["elapsed_ms"], 1500);
+        assert_eq!(value["invalid_users"], json!(["bob", "tom"]));
+        assert_eq!(value["results"]["3"]["ok"]["msgid"], "C");
+        assert_eq!(value["results"]["4"]["error"]["code"], 60011);
+    }
+}
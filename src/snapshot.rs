@@ -0,0 +1,188 @@
+use crate::{WecomAgent, WecomAgentBuilder, WecomError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Agent状态快照，用于在无服务器（Serverless）环境的多次调用间保留access token
+///
+/// 快照包含secret与access token，请妥善保存。
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WecomAgentSnapshot {
+    pub corp_id: String,
+    pub secret: String,
+    /// 缓存的access token，尚未获取时为None
+    pub token_value: Option<String>,
+    /// token更新时刻，Unix时间戳（秒）
+    pub token_timestamp_secs: u64,
+    /// token有效期（秒）
+    pub token_lifetime_secs: u64,
+}
+
+// 不输出secret与token
+impl fmt::Debug for WecomAgentSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WecomAgentSnapshot")
+            .field("corp_id", &self.corp_id)
+            .field("token_timestamp_secs", &self.token_timestamp_secs)
+            .field("token_lifetime_secs", &self.token_lifetime_secs)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WecomAgent {
+    /// 导出当前状态的快照
+    pub async fn to_snapshot(&self) -> WecomAgentSnapshot {
+        let access_token = self.access_token.read().await;
+        WecomAgentSnapshot {
+            corp_id: self.corp_id.clone(),
+            secret: self.secret.read().expect("Secret lock poisoned").clone(),
+            token_value: access_token.value().cloned(),
+            token_timestamp_secs: access_token
+                .timestamp()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            token_lifetime_secs: access_token.lifetime.as_secs(),
+        }
+    }
+
+    /// 由快照创建Agent。快照中的token仍有效时直接沿用，不会重新获取；已过期时在首次使用前更新。
+    ///
+    /// 快照不含API地址、代理、时钟等配置，以默认配置创建Agent。需要自定义配置时使用
+    /// `WecomAgentBuilder::restore()`。
+    pub fn from_snapshot(snapshot: WecomAgentSnapshot) -> Self {
+        WecomAgentBuilder::new(&snapshot.corp_id, &snapshot.secret)
+            .restore(snapshot)
+            .expect("Failed to build wecom agent")
+    }
+
+    // 恢复快照中尚未过期的token
+    fn restore_token(&mut self, snapshot: &WecomAgentSnapshot) {
+        let Some(token) = &snapshot.token_value else {
+            return;
+        };
+        let access_token = self.access_token.get_mut();
+        access_token.update(
+            token,
+            UNIX_EPOCH + Duration::from_secs(snapshot.token_timestamp_secs),
+            Duration::from_secs(snapshot.token_lifetime_secs),
+        );
+        if access_token.expired() {
+            access_token.invalidate();
+        }
+    }
+}
+
+impl WecomAgentBuilder {
+    /// 以构造器的配置与快照中的凭据、token创建Agent。快照中的corp_id与secret优先于构造器中的值。
+    pub fn restore(mut self, snapshot: WecomAgentSnapshot) -> Result<WecomAgent, WecomError> {
+        self.corp_id = snapshot.corp_id.clone();
+        self.secret = snapshot.secret.clone();
+        let mut agent = self.build()?;
+        agent.restore_token(&snapshot);
+        Ok(agent)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MockClock;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cgi-bin/gettoken"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "errcode": 0, "errmsg": "ok", "access_token": "token", "expires_in": 7200
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let agent = WecomAgent::builder("corp", "secret")
+            .base_url(&server.uri())
+            .build()
+            .unwrap();
+        agent.update_token(10).await.unwrap();
+
+        let snapshot = agent.to_snapshot().await;
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(!format!("{snapshot:?}").contains("\"secret\""));
+        let restored: WecomAgentSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, snapshot);
+        assert_eq!(restored.token_value.as_deref(), Some("token"));
+        assert_eq!(restored.token_lifetime_secs, 7200);
+
+        let agent = WecomAgent::from_snapshot(restored);
+        assert_eq!(agent.corp_id, "corp");
+        assert_eq!(agent.valid_token().await.unwrap(), "token");
+        assert_eq!(agent.to_snapshot().await, snapshot);
+    }
+
+    #[tokio::test]
+    async fn test_builder_restore() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cgi-bin/gettoken"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "errcode": 0, "errmsg": "ok", "access_token": "fresh", "expires_in": 7200
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let snapshot = WecomAgentSnapshot {
+            corp_id: "corp".to_string(),
+            secret: "secret".to_string(),
+            token_value: Some("token".to_string()),
+            token_timestamp_secs: 1_700_000_000,
+            token_lifetime_secs: 7200,
+        };
+
+        // 沿用构造器的时钟：按MockClock判断快照中的token仍有效
+        let clock = Arc::new(MockClock::new(now));
+        let agent = WecomAgent::builder("other", "other")
+            .base_url(&server.uri())
+            .with_clock(clock.clone())
+            .restore(snapshot)
+            .unwrap();
+        assert_eq!(agent.corp_id, "corp");
+        assert_eq!(agent.valid_token().await.unwrap(), "token");
+
+        // token过期后向构造器配置的API地址重新获取
+        clock.advance(Duration::from_secs(7200));
+        assert_eq!(agent.valid_token().await.unwrap(), "fresh");
+    }
+
+    #[test]
+    fn test_expired_snapshot() {
+        let snapshot = WecomAgentSnapshot {
+            corp_id: "corp".to_string(),
+            secret: "secret".to_string(),
+            token_value: Some("token".to_string()),
+            token_timestamp_secs: 1_700_000_000,
+            token_lifetime_secs: 7200,
+        };
+        let mut agent = WecomAgent::builder("corp", "secret")
+            .with_clock(Arc::new(MockClock::new(
+                UNIX_EPOCH + Duration::from_secs(1_700_000_000 + 7200),
+            )))
+            .build()
+            .unwrap();
+        agent.restore_token(&snapshot);
+        assert!(!agent.access_token.get_mut().is_initialized());
+
+        let agent = WecomAgent::from_snapshot(WecomAgentSnapshot {
+            token_timestamp_secs: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            ..snapshot
+        });
+        assert!(agent.access_token.try_read().unwrap().is_initialized());
+    }
+}
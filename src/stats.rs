@@ -0,0 +1,42 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Agent创建以来的累计调用统计
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WecomAgentStatistics {
+    /// 发送成功（errcode为0）的应用消息数
+    pub total_sent: u64,
+    /// 发送失败的应用消息数，含请求出错与errcode非0
+    pub total_failed: u64,
+    /// 成功更新access token的次数
+    pub total_token_refreshes: u64,
+    /// 更新access token失败的次数，不含因频率管制而未发出请求的情形
+    pub total_token_refresh_failures: u64,
+    /// 成功上传的临时素材数
+    pub total_media_uploads: u64,
+}
+
+// 各项统计的计数器
+#[derive(Debug, Default)]
+pub(crate) struct AgentCounters {
+    pub sent: AtomicU64,
+    pub failed: AtomicU64,
+    pub token_refreshes: AtomicU64,
+    pub token_refresh_failures: AtomicU64,
+    pub media_uploads: AtomicU64,
+}
+
+impl AgentCounters {
+    pub fn increment(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> WecomAgentStatistics {
+        WecomAgentStatistics {
+            total_sent: self.sent.load(Ordering::Relaxed),
+            total_failed: self.failed.load(Ordering::Relaxed),
+            total_token_refreshes: self.token_refreshes.load(Ordering::Relaxed),
+            total_token_refresh_failures: self.token_refresh_failures.load(Ordering::Relaxed),
+            total_media_uploads: self.media_uploads.load(Ordering::Relaxed),
+        }
+    }
+}
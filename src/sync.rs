@@ -0,0 +1,107 @@
+use crate::{MsgSendResponse, WecomAgent, WecomError};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+/// `WecomAgent`的阻塞式封装，供命令行工具等非异步场景使用。
+///
+/// 内部持有一个单线程tokio运行时。不可在异步上下文中调用其方法，否则会panic。
+///
+/// ```rust,no_run
+/// use wecom_agent::{message::{MessageBuilder, Text}, SyncWecomAgent};
+///
+/// let agent = SyncWecomAgent::new("your_corpid", "your_secret");
+/// let msg = MessageBuilder::default()
+///     .to_users(vec!["robin"])
+///     .from_agent(42)
+///     .build(Text::new("Hello".to_string()))
+///     .expect("Massage should be built");
+/// let response = agent.send(msg);
+/// ```
+#[derive(Debug)]
+pub struct SyncWecomAgent {
+    agent: WecomAgent,
+    runtime: Arc<Runtime>,
+}
+
+impl SyncWecomAgent {
+    pub fn new(corp_id: &str, secret: &str) -> Self {
+        Self::from_agent(WecomAgent::new(corp_id, secret))
+    }
+
+    /// 以环境变量`WECOM_CORP_ID`与`WECOM_SECRET`创建，参见`WecomAgent::from_env()`。
+    pub fn from_env() -> Result<Self, WecomError> {
+        Ok(Self::from_agent(WecomAgent::from_env()?))
+    }
+
+    /// 封装已创建的Agent，以便使用构造器定制的选项。
+    pub fn from_agent(agent: WecomAgent) -> Self {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Tokio runtime should be built");
+        Self {
+            agent,
+            runtime: Arc::new(runtime),
+        }
+    }
+
+    /// 发送应用消息，阻塞至发送完成。
+    pub fn send<T>(&self, msg: T) -> Result<MsgSendResponse, WecomError>
+    where
+        T: Serialize,
+    {
+        self.runtime.block_on(self.agent.send(msg))
+    }
+
+    /// 被封装的异步Agent
+    pub fn agent(&self) -> &WecomAgent {
+        &self.agent
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::message::{MessageBuilder, Text};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_blocking_send() {
+        // 模拟服务运行在独立的运行时中
+        let runtime = Runtime::new().unwrap();
+        let server = runtime.block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/cgi-bin/gettoken"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "errcode": 0, "errmsg": "ok", "access_token": "token", "expires_in": 7200
+                })))
+                .mount(&server)
+                .await;
+            Mock::given(method("POST"))
+                .and(path("/cgi-bin/message/send"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "errcode": 0, "errmsg": "ok", "msgid": "MSGID"
+                })))
+                .mount(&server)
+                .await;
+            server
+        });
+
+        let agent = SyncWecomAgent::from_agent(
+            WecomAgent::builder("corp", "secret")
+                .base_url(&server.uri())
+                .build()
+                .unwrap(),
+        );
+        let msg = MessageBuilder::default()
+            .to_users(vec!["robin"])
+            .from_agent(1)
+            .build(Text::new("hello".to_string()))
+            .unwrap();
+        let response = agent.send(msg).unwrap();
+        assert_eq!(response.msgid(), Some("MSGID"));
+    }
+}
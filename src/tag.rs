@@ -0,0 +1,246 @@
+//! 通讯录标签
+//!
+//! 标签可包含成员与部门，发送消息时以`totag`指定标签即可发给其中的全部成员。
+
+use crate::message::check_length;
+use crate::WecomError;
+use serde::{Deserialize, Serialize};
+
+// 标签名称的最大字符数
+const MAX_TAG_NAME_CHARS: usize = 32;
+// 单次增删的成员数与部门数上限
+pub(crate) const MAX_TAG_USERS: usize = 1000;
+pub(crate) const MAX_TAG_PARTIES: usize = 100;
+
+// 检查标签名称
+pub(crate) fn check_tag_name(name: &str) -> Result<(), WecomError> {
+    if name.is_empty() {
+        return Err(WecomError::InvalidArgument("标签名称不可为空".to_string()));
+    }
+    check_length("tag.tagname", name, MAX_TAG_NAME_CHARS)
+}
+
+// 增删标签成员的请求
+// {
+//     "tagid": 12,
+//     "userlist": ["user1", "user2"],
+//     "partylist": [4]
+// }
+#[derive(Debug, Serialize)]
+pub(crate) struct TagUsersRequest<'a> {
+    pub(crate) tagid: u64,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub(crate) userlist: &'a [&'a str],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub(crate) partylist: &'a [u64],
+}
+
+impl TagUsersRequest<'_> {
+    // 成员与部门不可同时为空，且分别不超过1000个与100个
+    pub(crate) fn validate(&self) -> Result<(), WecomError> {
+        if self.userlist.is_empty() && self.partylist.is_empty() {
+            return Err(WecomError::InvalidArgument(
+                "成员与部门不可同时为空".to_string(),
+            ));
+        }
+        if self.userlist.len() > MAX_TAG_USERS {
+            return Err(WecomError::InvalidArgument(format!(
+                "单次最多{MAX_TAG_USERS}个成员，实际{}个",
+                self.userlist.len()
+            )));
+        }
+        if self.partylist.len() > MAX_TAG_PARTIES {
+            return Err(WecomError::InvalidArgument(format!(
+                "单次最多{MAX_TAG_PARTIES}个部门，实际{}个",
+                self.partylist.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+// 创建标签的返回结果
+// {
+//     "errcode": 0,
+//     "errmsg": "created",
+//     "tagid": 12
+// }
+#[derive(Deserialize)]
+pub(crate) struct CreateTagResponse {
+    pub(crate) tagid: u64,
+}
+
+// 增删标签成员的返回结果。部分成员或部门无效时errcode仍为0。
+// {
+//     "errcode": 0,
+//     "errmsg": "ok",
+//     "invalidlist": "usr1|usr2|usr",
+//     "invalidparty": [2, 4]
+// }
+/// 增删标签成员的结果，列出未能处理的成员与部门
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct TagUsersResult {
+    #[serde(default)]
+    invalidlist: Option<String>,
+    #[serde(default)]
+    invalidparty: Vec<u64>,
+}
+
+impl TagUsersResult {
+    /// 无效或无权限的成员
+    pub fn invalid_users(&self) -> Vec<String> {
+        crate::split_ids(self.invalidlist.as_deref())
+    }
+
+    /// 无效或无权限的部门
+    pub fn invalid_parties(&self) -> &[u64] {
+        &self.invalidparty
+    }
+
+    /// 是否全部处理成功
+    pub fn is_complete(&self) -> bool {
+        self.invalid_users().is_empty() && self.invalidparty.is_empty()
+    }
+}
+
+// 获取标签成员的返回结果
+// {
+//     "errcode": 0,
+//     "errmsg": "ok",
+//     "tagname": "乒乓球协会",
+//     "userlist": [
+//         {"userid": "zhangsan", "name": "李四"}
+//     ],
+//     "partylist": [2]
+// }
+/// 标签的成员与部门
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagMembers {
+    #[serde(rename = "tagname")]
+    pub tag_name: String,
+    /// 标签中的成员
+    #[serde(rename = "userlist", default)]
+    pub users: Vec<TagUser>,
+    /// 标签中的部门
+    #[serde(rename = "partylist", default)]
+    pub parties: Vec<u64>,
+}
+
+/// 标签中的成员。应用无权查看姓名时`name`为None。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagUser {
+    pub userid: String,
+    pub name: Option<String>,
+}
+
+// 获取标签列表的返回结果
+// {
+//     "errcode": 0,
+//     "errmsg": "ok",
+//     "taglist": [
+//         {"tagid": 1, "tagname": "a"},
+//         {"tagid": 2, "tagname": "b"}
+//     ]
+// }
+#[derive(Deserialize)]
+pub(crate) struct TagListResponse {
+    #[serde(default)]
+    pub(crate) taglist: Vec<Tag>,
+}
+
+/// 标签
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tag {
+    #[serde(rename = "tagid")]
+    pub tag_id: u64,
+    #[serde(rename = "tagname")]
+    pub tag_name: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_tag_users_request() {
+        let request = TagUsersRequest {
+            tagid: 12,
+            userlist: &["robin", "tom"],
+            partylist: &[],
+        };
+        assert!(request.validate().is_ok());
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({"tagid": 12, "userlist": ["robin", "tom"]})
+        );
+
+        let users = crate::user_ids(MAX_TAG_USERS + 1);
+        let users: Vec<&str> = users.iter().map(String::as_str).collect();
+        for request in [
+            TagUsersRequest {
+                tagid: 12,
+                userlist: &[],
+                partylist: &[],
+            },
+            TagUsersRequest {
+                tagid: 12,
+                userlist: &users,
+                partylist: &[],
+            },
+        ] {
+            assert!(matches!(
+                request.validate(),
+                Err(WecomError::InvalidArgument(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_deserialize_tag_responses() {
+        let raw =
+            r#"{"errcode": 0, "errmsg": "ok", "invalidlist": "usr1|usr2", "invalidparty": [2, 4]}"#;
+        let result: TagUsersResult = serde_json::from_str(raw).unwrap();
+        assert_eq!(result.invalid_users(), ["usr1", "usr2"]);
+        assert_eq!(result.invalid_parties(), [2, 4]);
+        assert!(!result.is_complete());
+        let result: TagUsersResult =
+            serde_json::from_str(r#"{"errcode": 0, "errmsg": "ok"}"#).unwrap();
+        assert!(result.is_complete());
+
+        let raw = r#"{
+            "errcode": 0,
+            "errmsg": "ok",
+            "tagname": "乒乓球协会",
+            "userlist": [{"userid": "zhangsan", "name": "李四"}, {"userid": "lisi"}],
+            "partylist": [2]
+        }"#;
+        let members: TagMembers = serde_json::from_str(raw).unwrap();
+        assert_eq!(members.tag_name, "乒乓球协会");
+        assert_eq!(members.users[1].name, None);
+        assert_eq!(members.parties, [2]);
+
+        let raw = r#"{"errcode": 0, "errmsg": "ok", "taglist": [{"tagid": 1, "tagname": "a"}]}"#;
+        let response: TagListResponse = serde_json::from_str(raw).unwrap();
+        assert_eq!(
+            response.taglist,
+            [Tag {
+                tag_id: 1,
+                tag_name: "a".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_check_tag_name() {
+        assert!(check_tag_name("告警订阅").is_ok());
+        assert!(matches!(
+            check_tag_name(""),
+            Err(WecomError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            check_tag_name(&"标".repeat(33)),
+            Err(WecomError::ContentTooLong { .. })
+        ));
+    }
+}
@@ -0,0 +1,88 @@
+//! # 可观测性
+//!
+//! `wecom-agent`通过[`tracing`](https://docs.rs/tracing)输出span与事件。未设置subscriber时，
+//! 事件会转发给[`log`](https://docs.rs/log)，因此仍可使用`env_logger`等日志实现。
+//!
+//! ## 设置subscriber
+//! ```rust,no_run
+//! tracing_subscriber::fmt()
+//!     .with_max_level(tracing::Level::DEBUG)
+//!     .init();
+//! ```
+//!
+//! ## Span
+//! | 名称 | 来源 | 字段 |
+//! | --- | --- | --- |
+//! | `ping` | [`WecomAgent::ping`](crate::WecomAgent::ping) | `corp_id` |
+//! | `rotate_secret` | [`WecomAgent::rotate_secret`](crate::WecomAgent::rotate_secret) | `corp_id` |
+//! | `send_file` | [`WecomAgent::send_file`](crate::WecomAgent::send_file) | `corp_id`、`agent_id` |
+//! | `send_image` | [`WecomAgent::send_image`](crate::WecomAgent::send_image) | `corp_id`、`agent_id`、`filename` |
+//! | `send_deduped` | [`WecomAgent::send_deduped`](crate::WecomAgent::send_deduped) | `corp_id`、`dedup_key` |
+//! | `send_image_bytes` | [`WecomAgent::send_image_bytes`](crate::WecomAgent::send_image_bytes) | `corp_id`、`size` |
+//! | `send` | [`WecomAgent::send`](crate::WecomAgent::send) | `corp_id`、`msg_type` |
+//! | `send_chat` | [`WecomAgent::send_chat`](crate::WecomAgent::send_chat) | `corp_id`、`chat_id`、`msg_type` |
+//! | `send_linkedcorp` | [`WecomAgent::send_linkedcorp`](crate::WecomAgent::send_linkedcorp) | `corp_id`、`msg_type` |
+//! | `send_with_correlation` | [`WecomAgent::send_with_correlation`](crate::WecomAgent::send_with_correlation) | `corp_id`、`correlation_id` |
+//! | `send_with_config` | [`WecomAgent::send_with_config`](crate::WecomAgent::send_with_config) | `corp_id`、`msg_type`、`trace_id` |
+//! | `send_checked` | [`WecomAgent::send_checked`](crate::WecomAgent::send_checked) | `corp_id`、`msg_type` |
+//! | `send_detailed` | [`WecomAgent::send_detailed`](crate::WecomAgent::send_detailed) | `corp_id` |
+//! | `send_news` | [`WecomAgent::send_news`](crate::WecomAgent::send_news) | `corp_id`、`msg_type`、`agent_id` |
+//! | `send_text_card` | [`WecomAgent::send_text_card`](crate::WecomAgent::send_text_card) | `corp_id`、`agent_id` |
+//! | `create_chat` | [`WecomAgent::create_chat`](crate::WecomAgent::create_chat) | `corp_id`、`name` |
+//! | `get_chat` | [`WecomAgent::get_chat`](crate::WecomAgent::get_chat) | `corp_id`、`chat_id` |
+//! | `get_user` | [`WecomAgent::get_user`](crate::WecomAgent::get_user) | `corp_id`、`userid` |
+//! | `list_departments` | [`WecomAgent::list_departments`](crate::WecomAgent::list_departments) | `corp_id`、`id` |
+//! | `list_departments_simple` | [`WecomAgent::list_departments_simple`](crate::WecomAgent::list_departments_simple) | `corp_id`、`id` |
+//! | `create_tag` | [`WecomAgent::create_tag`](crate::WecomAgent::create_tag) | `corp_id`、`name` |
+//! | `add_tag_users` | [`WecomAgent::add_tag_users`](crate::WecomAgent::add_tag_users) | `corp_id`、`tagid` |
+//! | `remove_tag_users` | [`WecomAgent::remove_tag_users`](crate::WecomAgent::remove_tag_users) | `corp_id`、`tagid` |
+//! | `get_tag_members` | [`WecomAgent::get_tag_members`](crate::WecomAgent::get_tag_members) | `corp_id`、`tagid` |
+//! | `list_tags` | [`WecomAgent::list_tags`](crate::WecomAgent::list_tags) | `corp_id` |
+//! | `get_agent` | [`WecomAgent::get_agent`](crate::WecomAgent::get_agent) | `corp_id`、`agent_id` |
+//! | `list_agents` | [`WecomAgent::list_agents`](crate::WecomAgent::list_agents) | `corp_id` |
+//! | `set_agent` | [`WecomAgent::set_agent`](crate::WecomAgent::set_agent) | `corp_id`、`agent_id` |
+//! | `create_menu` | [`WecomAgent::create_menu`](crate::WecomAgent::create_menu) | `corp_id`、`agent_id` |
+//! | `get_menu` | [`WecomAgent::get_menu`](crate::WecomAgent::get_menu) | `corp_id`、`agent_id` |
+//! | `delete_menu` | [`WecomAgent::delete_menu`](crate::WecomAgent::delete_menu) | `corp_id`、`agent_id` |
+//! | `get_api_domain_ips` | [`WecomAgent::get_api_domain_ips`](crate::WecomAgent::get_api_domain_ips) | `corp_id` |
+//! | `get_callback_ips` | [`WecomAgent::get_callback_ips`](crate::WecomAgent::get_callback_ips) | `corp_id` |
+//! | `get_api_domain_ip_nets` | `WecomAgent::get_api_domain_ip_nets`（`ipnet` feature） | `corp_id` |
+//! | `get_callback_ip_nets` | `WecomAgent::get_callback_ip_nets`（`ipnet` feature） | `corp_id` |
+//! | `userid_to_openid` | [`WecomAgent::userid_to_openid`](crate::WecomAgent::userid_to_openid) | `corp_id`、`userid` |
+//! | `openid_to_userid` | [`WecomAgent::openid_to_userid`](crate::WecomAgent::openid_to_userid) | `corp_id`、`openid` |
+//! | `list_users` | [`WecomAgent::list_users`](crate::WecomAgent::list_users) | `corp_id`、`department_id` |
+//! | `list_users_simple` | [`WecomAgent::list_users_simple`](crate::WecomAgent::list_users_simple) | `corp_id`、`department_id` |
+//! | `get_media` | [`WecomAgent::get_media`](crate::WecomAgent::get_media) | `corp_id`、`media_id` |
+//! | `get_hd_voice` | [`WecomAgent::get_hd_voice`](crate::WecomAgent::get_hd_voice) | `corp_id`、`media_id` |
+//! | `get_media_to_file` | [`WecomAgent::get_media_to_file`](crate::WecomAgent::get_media_to_file) | `corp_id`、`media_id`、`path` |
+//! | `recall_message` | [`WecomAgent::recall_message`](crate::WecomAgent::recall_message) | `corp_id`、`msgid` |
+//! | `update_template_card` | [`WecomAgent::update_template_card`](crate::WecomAgent::update_template_card) | `corp_id`、`response_code`、`agent_id` |
+//! | `webhook_send` | [`WebhookAgent::send`](crate::WebhookAgent::send) | `msg_type` |
+//! | `webhook_upload_media` | [`WebhookAgent::upload_media`](crate::WebhookAgent::upload_media) | `media_type`、`size` |
+//! | `update_chat` | [`WecomAgent::update_chat`](crate::WecomAgent::update_chat) | `corp_id`、`chat_id` |
+//! | `update_taskcard` | [`WecomAgent::update_taskcard`](crate::WecomAgent::update_taskcard) | `corp_id`、`task_id`、`agent_id` |
+//! | `update_token` | [`WecomAgent::update_token`](crate::WecomAgent::update_token) | `corp_id`、`backoff_seconds` |
+//! | `upload_media` | [`WecomAgent::upload_media`](crate::WecomAgent::upload_media) | `corp_id`、`media_type`、`filename`、`size` |
+//! | `upload_media_unchecked` | [`WecomAgent::upload_media_unchecked`](crate::WecomAgent::upload_media_unchecked) | `corp_id`、`media_type`、`filename`、`size` |
+//! | `upload_image` | [`WecomAgent::upload_image`](crate::WecomAgent::upload_image) | `corp_id`、`filename`、`size` |
+//! | `upload_media_file` | [`WecomAgent::upload_media_file`](crate::WecomAgent::upload_media_file) | `corp_id`、`media_type`、`path` |
+//! | `upload_media_file_with_progress` | [`WecomAgent::upload_media_file_with_progress`](crate::WecomAgent::upload_media_file_with_progress) | `corp_id`、`media_type`、`path` |
+//! | `upload_media_with_progress` | [`WecomAgent::upload_media_with_progress`](crate::WecomAgent::upload_media_with_progress) | `corp_id`、`media_type`、`filename`、`total_size` |
+//!
+//! ## 事件
+//! - `WARN` `Token invalid. Updating...`：access token缺失、即将过期或被服务端弃用，即将更新。
+//! - `DEBUG` `Fetching access token...`：正在向服务端请求新的access token。
+//! - `INFO` `Token updated`：access token更新成功。
+//! - `DEBUG` `Sending [try N]...`、`Sending [Done]`：消息发送的各次尝试。
+//! - `DEBUG` `Error code N, waiting ... before retry...`：收到需重试的错误码，等待后重新发送。
+//! - `WARN` `Sending timed out`：发送超出了`SendConfig::timeout`规定的时限。
+//! - `DEBUG` `Uploading media...`、`Uploading media [Done]`：素材上传。
+//! - `DEBUG` `Downloading media...`、`Downloading media [Done]`：素材下载。
+//! - `DEBUG` `Uploading image...`、`Uploading image [Done]`：图片上传。
+//! - `WARN` `Auto refresh failed: ... Retrying in ...`：后台自动更新access token失败，退避后重试。
+//! - `WARN` `expires_in...`：服务端返回的凭据有效期异常，已使用默认值或钳制后的值。
+//! - `WARN` `TLS certificate verification is disabled...`：启用了`danger_accept_invalid_certs()`，不得用于生产环境。
+//! - `DEBUG` `Reusing uploaded image ...`：`send_image_bytes()`复用了内容相同的已上传图片。
+//! - `DEBUG` `Message ... already sent. Skipped.`：`send_deduped()`的去重键已发送过，本次跳过。
+//! - `WARN` `Failed to persist dedup record ...`：`FileDeduplicator`写入去重记录失败。
+//! - `DEBUG` `Health check passed`、`WARN` `Health check failed: ...`：后台健康检查的结果。
@@ -0,0 +1,181 @@
+//! 通讯录成员
+
+use serde::{Deserialize, Serialize};
+
+// 读取成员的返回结果（节选）
+// {
+//     "errcode": 0,
+//     "errmsg": "ok",
+//     "userid": "zhangsan",
+//     "name": "张三",
+//     "department": [1, 2],
+//     "main_department": 1,
+//     "position": "后台工程师",
+//     "mobile": "13800000000",
+//     "email": "zhangsan@gzdev.com",
+//     "biz_mail": "zhangsan@qyycs2.wecom.work",
+//     "alias": "jackzhang",
+//     "status": 1
+// }
+/// 通讯录成员的信息
+///
+/// 除`userid`外，各字段是否返回取决于应用的通讯录权限。2022年6月20日后新创建的应用
+/// 无法获取手机号、邮箱等敏感字段，因此均为`Option`。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserInfo {
+    pub userid: String,
+    pub name: Option<String>,
+    /// 所属部门的ID
+    pub department: Option<Vec<u64>>,
+    /// 主部门的ID
+    pub main_department: Option<u64>,
+    /// 职务
+    pub position: Option<String>,
+    pub mobile: Option<String>,
+    pub email: Option<String>,
+    /// 企业邮箱
+    pub biz_mail: Option<String>,
+    /// 别名
+    pub alias: Option<String>,
+    /// 激活状态：1为已激活，2为已禁用，4为未激活，5为退出企业
+    pub status: Option<i64>,
+}
+
+impl UserInfo {
+    /// 是否已激活。未返回状态时视为未激活。
+    pub fn is_active(&self) -> bool {
+        self.status == Some(1)
+    }
+}
+
+// 获取部门成员的返回结果
+// {
+//     "errcode": 0,
+//     "errmsg": "ok",
+//     "userlist": [
+//         {
+//             "userid": "zhangsan",
+//             "name": "张三",
+//             "department": [1, 2],
+//             "open_userid": "xxxxxx"
+//         }
+//     ]
+// }
+#[derive(Deserialize)]
+pub(crate) struct UserListResponse<T> {
+    #[serde(default = "Vec::new")]
+    pub(crate) userlist: Vec<T>,
+}
+
+/// 部门成员的基础信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimpleUser {
+    pub userid: String,
+    pub name: Option<String>,
+    /// 所属部门的ID
+    pub department: Option<Vec<u64>>,
+    /// 全局唯一的成员ID，仅第三方应用返回
+    pub open_userid: Option<String>,
+}
+
+// userid转换为openid的返回结果
+// {
+//     "errcode": 0,
+//     "errmsg": "ok",
+//     "openid": "oDjGHs-1yCnGrRovBj2yHij5JAAA"
+// }
+#[derive(Deserialize)]
+pub(crate) struct ConvertToOpenIdResponse {
+    pub(crate) openid: String,
+}
+
+// openid转换为userid的返回结果
+// {
+//     "errcode": 0,
+//     "errmsg": "ok",
+//     "userid": "zhangsan"
+// }
+#[derive(Deserialize)]
+pub(crate) struct ConvertToUserIdResponse {
+    pub(crate) userid: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_user_info() {
+        let raw = r#"{
+            "errcode": 0,
+            "errmsg": "ok",
+            "userid": "zhangsan",
+            "name": "张三",
+            "department": [1, 2],
+            "order": [1, 2],
+            "position": "后台工程师",
+            "mobile": "13800000000",
+            "gender": "1",
+            "email": "zhangsan@gzdev.com",
+            "biz_mail": "zhangsan@qyycs2.wecom.work",
+            "is_leader_in_dept": [1, 0],
+            "direct_leader": ["lisi"],
+            "avatar": "http://wx.qlogo.cn/mmopen/ajNVdqHZLLA3WJ6DSZUfiakYe37PKnQhBIeOQBO4czqrnZDS79FH5Wm5m4X69TBicnHFlhiafvDwklOpZeXYQQ2icg/0",
+            "thumb_avatar": "http://wx.qlogo.cn/mmopen/ajNVdqHZLLA3WJ6DSZUfiakYe37PKnQhBIeOQBO4czqrnZDS79FH5Wm5m4X69TBicnHFlhiafvDwklOpZeXYQQ2icg/100",
+            "telephone": "020-123456",
+            "alias": "jackzhang",
+            "address": "广州市海珠区新港中路",
+            "open_userid": "xxxxxx",
+            "main_department": 1,
+            "extattr": {"attrs": []},
+            "status": 1,
+            "qr_code": "https://open.work.weixin.qq.com/wwopen/userQRCode?vcode=xxx",
+            "external_position": "产品经理"
+        }"#;
+        let user: UserInfo = serde_json::from_str(raw).unwrap();
+        assert_eq!(
+            user,
+            UserInfo {
+                userid: "zhangsan".to_string(),
+                name: Some("张三".to_string()),
+                department: Some(vec![1, 2]),
+                main_department: Some(1),
+                position: Some("后台工程师".to_string()),
+                mobile: Some("13800000000".to_string()),
+                email: Some("zhangsan@gzdev.com".to_string()),
+                biz_mail: Some("zhangsan@qyycs2.wecom.work".to_string()),
+                alias: Some("jackzhang".to_string()),
+                status: Some(1),
+            }
+        );
+        assert!(user.is_active());
+
+        // 无敏感字段权限的应用仅能获取部分字段
+        let raw = r#"{"errcode": 0, "errmsg": "ok", "userid": "lisi", "department": [3]}"#;
+        let user: UserInfo = serde_json::from_str(raw).unwrap();
+        assert_eq!(user.userid, "lisi");
+        assert_eq!(user.department, Some(vec![3]));
+        assert_eq!(user.mobile, None);
+        assert!(!user.is_active());
+    }
+
+    #[test]
+    fn test_deserialize_user_list() {
+        let raw = r#"{
+            "errcode": 0,
+            "errmsg": "ok",
+            "userlist": [
+                {"userid": "zhangsan", "name": "张三", "department": [1, 2], "open_userid": "xxxxxx"},
+                {"userid": "lisi", "department": [2]}
+            ]
+        }"#;
+        let response: UserListResponse<SimpleUser> = serde_json::from_str(raw).unwrap();
+        assert_eq!(response.userlist.len(), 2);
+        assert_eq!(response.userlist[0].name.as_deref(), Some("张三"));
+        assert_eq!(response.userlist[1].name, None);
+
+        let raw = r#"{"errcode": 0, "errmsg": "ok"}"#;
+        let response: UserListResponse<UserInfo> = serde_json::from_str(raw).unwrap();
+        assert!(response.userlist.is_empty());
+    }
+}
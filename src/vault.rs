@@ -0,0 +1,176 @@
+use crate::{WecomAgent, WecomAgentBuilder, WecomError};
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::warn;
+use vaultrs::client::{VaultClient, VaultClientSettingsBuilder};
+use vaultrs::error::ClientError;
+
+// 无法连接Vault时的重试次数与间隔
+const VAULT_MAX_RETRIES: u32 = 3;
+const VAULT_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+// Vault中保存的企业微信凭据
+#[derive(Deserialize)]
+struct VaultCredentials {
+    corp_id: String,
+    corp_secret: String,
+}
+
+impl WecomAgent {
+    /// 从HashiCorp Vault的KV v2密钥读取企业微信凭据并创建Agent。注意此过程不会自动初始化access token。
+    ///
+    /// `secret_path`的第一段为KV引擎的挂载点，其余为密钥路径，如`secret/wecom/prod`。
+    /// 密钥须包含以下两个字符串字段，其他字段将被忽略：
+    ///
+    /// ```json
+    /// { "corp_id": "企业ID", "corp_secret": "应用的Secret" }
+    /// ```
+    ///
+    /// 无法连接Vault时每隔2秒重试，最多重试3次。仍然失败，或Vault拒绝请求、密钥缺少字段时，
+    /// 返回`WecomError::VaultError`。
+    pub async fn from_vault(
+        vault_addr: &str,
+        vault_token: &str,
+        secret_path: &str,
+    ) -> Result<Self, WecomError> {
+        let (mount, path) = split_secret_path(secret_path)?;
+        let settings = VaultClientSettingsBuilder::default()
+            .address(vault_addr)
+            .token(vault_token)
+            .build()
+            .map_err(|e| WecomError::VaultError(format!("Vault配置无效: {e}")))?;
+        let client = VaultClient::new(settings)
+            .map_err(|e| WecomError::VaultError(format!("无法创建Vault客户端: {e}")))?;
+
+        let mut retries = 0;
+        let credentials: VaultCredentials = loop {
+            match vaultrs::kv2::read(&client, mount, path).await {
+                Ok(credentials) => break credentials,
+                Err(ClientError::RestClientError { source }) if retries < VAULT_MAX_RETRIES => {
+                    retries += 1;
+                    warn!(
+                        "Failed to connect to Vault, retry {retries}/{VAULT_MAX_RETRIES}: {source}"
+                    );
+                    tokio::time::sleep(VAULT_RETRY_DELAY).await;
+                }
+                Err(e) => {
+                    return Err(WecomError::VaultError(format!(
+                        "读取{secret_path}失败: {e}"
+                    )))
+                }
+            }
+        };
+        WecomAgentBuilder::new(&credentials.corp_id, &credentials.corp_secret).build()
+    }
+}
+
+// 将`secret_path`拆分为挂载点与密钥路径
+fn split_secret_path(secret_path: &str) -> Result<(&str, &str), WecomError> {
+    match secret_path.trim_matches('/').split_once('/') {
+        Some((mount, path)) if !mount.is_empty() && !path.is_empty() => Ok((mount, path)),
+        _ => Err(WecomError::InvalidArgument(format!(
+            "Vault密钥路径须包含挂载点与路径，如secret/wecom: {secret_path}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn secret_response(data: serde_json::Value) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(json!({
+            "request_id": "6f1a2b3c",
+            "lease_id": "",
+            "renewable": false,
+            "lease_duration": 0,
+            "data": {
+                "data": data,
+                "metadata": {
+                    "created_time": "2024-03-01T08:00:00.000000Z",
+                    "custom_metadata": null,
+                    "deletion_time": "",
+                    "destroyed": false,
+                    "version": 2
+                }
+            },
+            "wrap_info": null,
+            "warnings": null,
+            "auth": null
+        }))
+    }
+
+    #[test]
+    fn test_split_secret_path() {
+        assert_eq!(
+            split_secret_path("secret/wecom").unwrap(),
+            ("secret", "wecom")
+        );
+        assert_eq!(
+            split_secret_path("/kv/wecom/prod/").unwrap(),
+            ("kv", "wecom/prod")
+        );
+        assert!(split_secret_path("wecom").is_err());
+        assert!(split_secret_path("secret/").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_from_vault() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/secret/data/wecom"))
+            .and(header("X-Vault-Token", "vault-token"))
+            .respond_with(secret_response(
+                json!({"corp_id": "corp", "corp_secret": "secret", "note": "ignored"}),
+            ))
+            .mount(&server)
+            .await;
+
+        let agent = WecomAgent::from_vault(&server.uri(), "vault-token", "secret/wecom")
+            .await
+            .unwrap();
+        assert_eq!(agent.corp_id, "corp");
+    }
+
+    #[tokio::test]
+    async fn test_from_vault_rejected() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/secret/data/missing"))
+            .respond_with(secret_response(json!({"corp_id": "corp"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/secret/data/wecom"))
+            .respond_with(
+                ResponseTemplate::new(403).set_body_json(json!({"errors": ["permission denied"]})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // 密钥缺少字段或Vault拒绝请求时不重试
+        let err = WecomAgent::from_vault(&server.uri(), "t", "secret/missing")
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), WecomError::CODE_VAULT);
+        let err = WecomAgent::from_vault(&server.uri(), "t", "secret/wecom")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WecomError::VaultError(_)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_from_vault_retries_connection_failure() {
+        let start = tokio::time::Instant::now();
+        let err = WecomAgent::from_vault("http://127.0.0.1:1", "t", "secret/wecom")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WecomError::VaultError(_)));
+        assert_eq!(start.elapsed(), VAULT_RETRY_DELAY * VAULT_MAX_RETRIES);
+    }
+}
@@ -0,0 +1,486 @@
+use crate::media::{self, is_jpg, is_png, MediaType, TemporaryMedia};
+use crate::message::{check_bytes, FileMsg, MarkDownMsg, MessageType, WecomMessage};
+use crate::{LengthUnit, RequestContext, WecomError, DEFAULT_BASE_URL};
+use base64::Engine;
+use serde::Serialize;
+use serde_json::json;
+use std::fmt;
+
+// 群机器人发送消息的接口路径
+const WEBHOOK_PATH: &str = "/cgi-bin/webhook/send";
+// 群机器人上传文件的接口路径
+const WEBHOOK_UPLOAD_PATH: &str = "/cgi-bin/webhook/upload_media";
+
+/// 群机器人
+///
+/// 以webhook key向群聊发送消息，无需corp_id与secret，也不使用access token。
+/// 消息内容与应用消息通用，文本消息如需@成员请使用`WebhookText`。
+///
+/// ```rust,no_run
+/// use wecom_agent::{message::MarkDownMsg, WebhookAgent};
+///
+/// async fn example() {
+///     let robot = WebhookAgent::new("693a91f6-7xxx-4bc4-97a0-0ec2sifa5aaa");
+///     let content = MarkDownMsg::new("**值班提醒**".to_string());
+///     robot.send(content).await.expect("Message should be sent");
+/// }
+/// ```
+#[derive(Clone)]
+pub struct WebhookAgent {
+    client: reqwest::Client,
+    url: String,
+}
+
+// webhook地址含有key，不予输出
+impl fmt::Debug for WebhookAgent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookAgent").finish_non_exhaustive()
+    }
+}
+
+impl WebhookAgent {
+    /// 以webhook key或完整的webhook地址创建群机器人
+    pub fn new(key: &str) -> Self {
+        let url = if key.starts_with("https://") || key.starts_with("http://") {
+            key.to_owned()
+        } else {
+            format!("{DEFAULT_BASE_URL}{WEBHOOK_PATH}?key={key}")
+        };
+        Self {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+
+    /// 使用自定义的HTTP客户端，以便设置代理、超时等
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// 向群聊发送消息。errcode非0时返回`WecomError::Api`。
+    #[tracing::instrument(
+        name = "webhook_send",
+        skip_all,
+        fields(msg_type = content.msg_type().as_str())
+    )]
+    pub async fn send<T>(&self, content: T) -> Result<(), WecomError>
+    where
+        T: Serialize + WecomMessage,
+    {
+        content.validate()?;
+        let mut msg = json!({ "msgtype": content.msg_type().as_str() });
+        msg[content.key()] = serde_json::to_value(content.value())?;
+
+        let context = || RequestContext::for_message(WEBHOOK_PATH, &msg);
+        async {
+            let response = self.client.post(&self.url).json(&msg).send().await?;
+            crate::parse_api_response::<serde::de::IgnoredAny>(response).await
+        }
+        .await
+        .map_err(|e| e.with_context(context()))?;
+        Ok(())
+    }
+
+    /// 发送图片。图片以base64编码随消息发送，须为JPG或PNG格式且不超过2MB。
+    pub async fn send_image_bytes(&self, data: impl AsRef<[u8]>) -> Result<(), WecomError> {
+        let data = data.as_ref();
+        if !is_jpg(data) && !is_png(data) {
+            return Err(WecomError::InvalidArgument(
+                "图片仅支持JPG与PNG格式".to_string(),
+            ));
+        }
+        if data.len() > MAX_WEBHOOK_IMAGE_BYTES {
+            return Err(WecomError::ContentTooLong {
+                field: "image",
+                actual: data.len(),
+                limit: MAX_WEBHOOK_IMAGE_BYTES,
+                unit: LengthUnit::Bytes,
+            });
+        }
+        self.send(WebhookImage::new(data)).await
+    }
+
+    /// 发送Markdown消息，内容不超过4096字节
+    pub async fn send_markdown(&self, content: String) -> Result<(), WecomError> {
+        self.send(MarkDownMsg::new(content)).await
+    }
+
+    /// 上传文件或语音，所得media_id仅可用于该群机器人，3天内有效。
+    ///
+    /// 仅支持`MediaType::File`（不超过20MB）与`MediaType::Voice`（AMR格式，不超过2MB）。
+    /// 上传前以[`MediaType::validate()`]检查素材的大小与格式。
+    ///
+    /// 上传地址由webhook地址末尾的`/webhook/send`替换为`/webhook/upload_media`得到，保留网关添加的路径前缀。
+    #[tracing::instrument(
+        name = "webhook_upload_media",
+        skip_all,
+        fields(media_type = media_type.as_str(), size = data.len())
+    )]
+    pub async fn upload_media(
+        &self,
+        media_type: MediaType,
+        filename: &str,
+        data: Vec<u8>,
+    ) -> Result<TemporaryMedia, WecomError> {
+        if !matches!(media_type, MediaType::File | MediaType::Voice) {
+            return Err(WecomError::InvalidArgument(format!(
+                "群机器人仅支持上传file与voice素材，不支持{}",
+                media_type.as_str()
+            )));
+        }
+        media_type.validate(filename, &data)?;
+
+        let mut url = reqwest::Url::parse(&self.url)
+            .map_err(|e| WecomError::InvalidArgument(format!("webhook地址无效: {e}")))?;
+        let upload_path = match url.path().strip_suffix("/webhook/send") {
+            Some(prefix) => format!("{prefix}/webhook/upload_media"),
+            None => WEBHOOK_UPLOAD_PATH.to_string(),
+        };
+        url.set_path(&upload_path);
+        url.query_pairs_mut()
+            .append_pair("type", media_type.as_str());
+        let (content_type, body) = media::multipart_body(filename, &data);
+        async {
+            let response = self
+                .client
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(body)
+                .send()
+                .await?;
+            crate::parse_api_response::<TemporaryMedia>(response).await
+        }
+        .await
+        .map_err(|e| e.with_context(RequestContext::new(WEBHOOK_UPLOAD_PATH)))
+    }
+
+    /// 上传文件并发送，文件不超过20MB
+    pub async fn send_file(&self, filename: &str, data: Vec<u8>) -> Result<(), WecomError> {
+        let media = self.upload_media(MediaType::File, filename, data).await?;
+        self.send(FileMsg::new(media.media_id().to_owned())).await
+    }
+}
+
+/// 群机器人图片的字节数上限
+pub const MAX_WEBHOOK_IMAGE_BYTES: usize = 2 * 1024 * 1024;
+
+// 群机器人图片消息，以图片内容的base64编码与md5代替media_id
+// {
+//     "msgtype": "image",
+//     "image": {
+//         "base64": "DATA",
+//         "md5": "MD5"
+//     }
+// }
+#[derive(Debug, Serialize, PartialEq)]
+struct WebhookImage {
+    base64: String,
+    md5: String,
+}
+
+impl WebhookImage {
+    fn new(data: &[u8]) -> Self {
+        Self {
+            base64: base64::engine::general_purpose::STANDARD.encode(data),
+            md5: media::content_hash(data),
+        }
+    }
+}
+
+impl WecomMessage for WebhookImage {
+    fn msg_type(&self) -> MessageType {
+        MessageType::Image
+    }
+
+    fn key(&self) -> String {
+        "image".to_string()
+    }
+}
+
+// 群机器人文本消息
+// 示例
+// {
+//     "msgtype": "text",
+//     "text": {
+//         "content": "广州今日天气：29度，大部分多云，降雨概率：60%",
+//         "mentioned_list": ["wangqing", "@all"],
+//         "mentioned_mobile_list": ["13800001111", "@all"]
+//     }
+// }
+/// 群机器人的文本消息，可@指定成员
+#[derive(Debug, Serialize, PartialEq)]
+pub struct WebhookText {
+    content: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    mentioned_list: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    mentioned_mobile_list: Vec<String>,
+}
+
+/// 群机器人文本消息的字节数上限
+pub const MAX_WEBHOOK_TEXT_BYTES: usize = 2048;
+
+impl WebhookText {
+    pub fn new(content: String) -> Self {
+        Self {
+            content,
+            mentioned_list: Vec::new(),
+            mentioned_mobile_list: Vec::new(),
+        }
+    }
+
+    /// 以userid@成员，`@all`表示全体成员
+    pub fn with_mentioned_list(mut self, users: Vec<&str>) -> Self {
+        self.mentioned_list = users.into_iter().map(str::to_owned).collect();
+        self
+    }
+
+    /// 以手机号@成员，`@all`表示全体成员
+    pub fn with_mentioned_mobile_list(mut self, mobiles: Vec<&str>) -> Self {
+        self.mentioned_mobile_list = mobiles.into_iter().map(str::to_owned).collect();
+        self
+    }
+}
+
+impl WecomMessage for WebhookText {
+    fn msg_type(&self) -> MessageType {
+        MessageType::Text
+    }
+
+    fn key(&self) -> String {
+        "text".to_string()
+    }
+
+    fn validate(&self) -> Result<(), WecomError> {
+        check_bytes("text.content", &self.content, MAX_WEBHOOK_TEXT_BYTES)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wiremock::matchers::{body_json, body_string_contains, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_new() {
+        let robot = WebhookAgent::new("KEY");
+        assert_eq!(
+            robot.url,
+            "https://qyapi.weixin.qq.com/cgi-bin/webhook/send?key=KEY"
+        );
+        let url = "https://example.com/cgi-bin/webhook/send?key=KEY";
+        assert_eq!(WebhookAgent::new(url).url, url);
+        assert!(!format!("{robot:?}").contains("KEY"));
+    }
+
+    #[test]
+    fn test_webhook_image_encoding() {
+        let image = WebhookImage::new(b"");
+        assert_eq!(image.base64, "");
+        assert_eq!(image.md5, "d41d8cd98f00b204e9800998ecf8427e");
+
+        let image = WebhookImage::new(b"hello");
+        assert_eq!(image.base64, "aGVsbG8=");
+        assert_eq!(image.md5, "5d41402abc4b2a76b9719d911017c592");
+
+        // PNG文件头
+        let image = WebhookImage::new(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]);
+        assert_eq!(image.base64, "iVBORw0KGgo=");
+        assert_eq!(image.md5, "e9dd2797018cad79186e03e8c5aec8dc");
+    }
+
+    #[tokio::test]
+    async fn test_send_image_and_markdown() {
+        let server = MockServer::start().await;
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        let image = WebhookImage::new(&png);
+        Mock::given(method("POST"))
+            .and(path("/cgi-bin/webhook/send"))
+            .and(body_json(json!({
+                "msgtype": "image",
+                "image": {"base64": image.base64, "md5": image.md5}
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"errcode": 0, "errmsg": "ok"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/cgi-bin/webhook/send"))
+            .and(body_json(json!({
+                "msgtype": "markdown",
+                "markdown": {"content": "**值班提醒**"}
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"errcode": 0, "errmsg": "ok"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let robot = WebhookAgent::new(&format!("{}/cgi-bin/webhook/send?key=KEY", server.uri()));
+
+        robot.send_image_bytes(png).await.unwrap();
+        robot
+            .send_markdown("**值班提醒**".to_string())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            robot.send_image_bytes(b"GIF89a").await,
+            Err(WecomError::InvalidArgument(_))
+        ));
+        let mut large = png.to_vec();
+        large.resize(MAX_WEBHOOK_IMAGE_BYTES + 1, 0);
+        assert!(matches!(
+            robot.send_image_bytes(large).await,
+            Err(WecomError::ContentTooLong { .. })
+        ));
+        assert!(matches!(
+            robot.send_markdown("a".repeat(4097)).await,
+            Err(WecomError::ContentTooLong { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_send_file() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/cgi-bin/webhook/upload_media"))
+            .and(query_param("key", "KEY"))
+            .and(query_param("type", "file"))
+            .and(body_string_contains(
+                "filename=\"report.txt\"; filelength=6",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "errcode": 0,
+                "errmsg": "ok",
+                "type": "file",
+                "media_id": "MEDIA_ID",
+                "created_at": "1380000000"
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/cgi-bin/webhook/send"))
+            .and(query_param("key", "KEY"))
+            .and(body_json(json!({
+                "msgtype": "file",
+                "file": {"media_id": "MEDIA_ID"}
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"errcode": 0, "errmsg": "ok"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let robot = WebhookAgent::new(&format!("{}/cgi-bin/webhook/send?key=KEY", server.uri()));
+
+        let media = robot
+            .upload_media(MediaType::File, "report.txt", b"report".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(media.media_id(), "MEDIA_ID");
+        assert_eq!(media.media_type(), MediaType::File);
+        assert_eq!(
+            media.uploaded_at(),
+            std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_380_000_000)
+        );
+        robot
+            .send_file("report.txt", b"report".to_vec())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            robot
+                .upload_media(MediaType::Image, "a.png", b"report".to_vec())
+                .await,
+            Err(WecomError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            robot
+                .upload_media(MediaType::Voice, "a.amr", vec![0; 2 * 1024 * 1024 + 1])
+                .await,
+            Err(WecomError::ContentTooLong { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_upload_media_with_path_prefix() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/wecom-gateway/cgi-bin/webhook/upload_media"))
+            .and(query_param("key", "KEY"))
+            .and(query_param("type", "file"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "errcode": 0, "errmsg": "ok", "type": "file", "media_id": "MEDIA_ID", "created_at": "1380000000"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let robot = WebhookAgent::new(&format!(
+            "{}/wecom-gateway/cgi-bin/webhook/send?key=KEY",
+            server.uri()
+        ));
+
+        let media = robot
+            .upload_media(MediaType::File, "report.txt", b"report".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(media.media_id(), "MEDIA_ID");
+    }
+
+    #[tokio::test]
+    async fn test_send() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/cgi-bin/webhook/send"))
+            .and(query_param("key", "KEY"))
+            .and(body_json(json!({
+                "msgtype": "text",
+                "text": {"content": "值班提醒", "mentioned_list": ["robin", "@all"]}
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"errcode": 0, "errmsg": "ok"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/cgi-bin/webhook/send"))
+            .and(query_param("key", "KEY"))
+            .and(body_json(json!({
+                "msgtype": "markdown",
+                "markdown": {"content": "**值班提醒**"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "errcode": 93000, "errmsg": "invalid webhook url"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let robot = WebhookAgent::new(&format!("{}/cgi-bin/webhook/send?key=KEY", server.uri()));
+
+        let text =
+            WebhookText::new("值班提醒".to_string()).with_mentioned_list(vec!["robin", "@all"]);
+        robot.send(text).await.unwrap();
+
+        let e = robot
+            .send(MarkDownMsg::new("**值班提醒**".to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!(e.code(), 93000);
+        let context = e.context().unwrap();
+        assert_eq!(context.path(), "/cgi-bin/webhook/send");
+        assert_eq!(context.msg_type(), Some("markdown"));
+
+        let long = WebhookText::new("a".repeat(MAX_WEBHOOK_TEXT_BYTES + 1));
+        assert!(matches!(
+            robot.send(long).await,
+            Err(WecomError::ContentTooLong { .. })
+        ));
+    }
+}
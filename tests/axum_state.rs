@@ -0,0 +1,42 @@
+// 编译期检查`Arc<WecomAgent>`可作为axum的应用状态，并在处理函数中以`State`提取。
+// 若`send()`返回的Future不再满足Send，处理函数将无法注册，此处随即无法编译。
+
+use axum::extract::{FromRef, State};
+use axum::{routing::post, Router};
+use std::sync::Arc;
+use wecom_agent::{
+    message::{MessageBuilder, Text},
+    WecomAgent,
+};
+
+#[derive(Clone, FromRef)]
+struct AppState {
+    agent: Arc<WecomAgent>,
+}
+
+async fn alert(State(agent): State<Arc<WecomAgent>>, body: String) -> String {
+    let msg = MessageBuilder::default()
+        .to_users(vec!["robin"])
+        .from_agent(42)
+        .build(Text::new(body))
+        .expect("Massage should be built");
+    match agent.send(msg).await {
+        Ok(response) => response.error_code().to_string(),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[test]
+fn test_extract_agent_from_app_state() {
+    let state = AppState {
+        agent: Arc::new(WecomAgent::new("corp", "secret")),
+    };
+    let _app: Router = Router::new()
+        .route("/alert", post(alert))
+        .with_state(state.clone());
+
+    // 直接以Arc<WecomAgent>作为状态
+    let _app: Router = Router::new()
+        .route("/alert", post(alert))
+        .with_state(state.agent);
+}
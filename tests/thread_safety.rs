@@ -0,0 +1,15 @@
+// 编译期检查公开类型可跨线程使用。若新增字段破坏了Send或Sync，此处将无法编译。
+
+use wecom_agent::{MsgSendResponse, WecomAgent};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_wecom_agent_is_send_sync() {
+    assert_send_sync::<WecomAgent>();
+}
+
+#[test]
+fn test_msg_send_response_is_send_sync() {
+    assert_send_sync::<MsgSendResponse>();
+}